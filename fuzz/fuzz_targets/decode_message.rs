@@ -0,0 +1,32 @@
+#![no_main]
+// The server is a binary crate, so the codec modules are compiled into the target directly
+#![allow(dead_code)]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/edns.rs"]
+mod edns;
+#[path = "../../src/error.rs"]
+mod error;
+#[path = "../../src/idna.rs"]
+mod idna;
+#[path = "../../src/idna_tables.rs"]
+mod idna_tables;
+#[path = "../../src/message_ref.rs"]
+mod message_ref;
+#[path = "../../src/parse.rs"]
+mod parse;
+#[path = "../../src/rdata.rs"]
+mod rdata;
+#[path = "../../src/types.rs"]
+mod types;
+
+fuzz_target!(|data: &[u8]| {
+    // Decoding may fail, but must never panic
+    if let Ok(message) = types::Message::try_from(data) {
+        // Anything that decodes must survive being encoded and decoded again unchanged
+        let encoded = message.clone().encode(false);
+
+        assert_eq!(types::Message::try_from(encoded.as_slice()), Ok(message));
+    }
+});
@@ -0,0 +1,297 @@
+#!/usr/bin/env python3
+"""Generates src/idna_tables.rs from the Unicode character database that ships with Python.
+
+Which code points may appear in a label is worked out with the IDNA2008 algorithm of RFC 5892 §3,
+using the Unicode version of Python's unicodedata module. The few properties that module doesn't
+expose (Default_Ignorable_Code_Point, White_Space, Noncharacter_Code_Point and Hangul_Syllable_Type)
+are written out below from the same Unicode version's DerivedCoreProperties.txt, PropList.txt and
+HangulSyllableType.txt.
+
+Run it with the Python whose Unicode version the tables should follow:
+
+    python3 scripts/idna_tables.py > src/idna_tables.rs
+"""
+
+import random
+import unicodedata
+
+UNICODE_VERSION = "14.0.0"
+
+# RFC 5892 §2.6
+EXCEPTIONS = {
+    0x00DF: "PVALID", 0x03C2: "PVALID", 0x06FD: "PVALID", 0x06FE: "PVALID",
+    0x0F0B: "PVALID", 0x3007: "PVALID",
+    0x00B7: "CONTEXTO", 0x0375: "CONTEXTO", 0x05F3: "CONTEXTO", 0x05F4: "CONTEXTO",
+    0x30FB: "CONTEXTO",
+    0x0640: "DISALLOWED", 0x07FA: "DISALLOWED", 0x302E: "DISALLOWED", 0x302F: "DISALLOWED",
+    0x3031: "DISALLOWED", 0x3032: "DISALLOWED", 0x3033: "DISALLOWED", 0x3034: "DISALLOWED",
+    0x3035: "DISALLOWED", 0x303B: "DISALLOWED",
+}
+EXCEPTIONS.update({cp: "CONTEXTO" for cp in range(0x0660, 0x066A)})
+EXCEPTIONS.update({cp: "CONTEXTO" for cp in range(0x06F0, 0x06FA)})
+
+# DerivedCoreProperties.txt
+DEFAULT_IGNORABLE = [
+    (0x00AD, 0x00AD), (0x034F, 0x034F), (0x061C, 0x061C), (0x115F, 0x1160),
+    (0x17B4, 0x17B5), (0x180B, 0x180F), (0x200B, 0x200F), (0x202A, 0x202E),
+    (0x2060, 0x206F), (0x3164, 0x3164), (0xFE00, 0xFE0F), (0xFEFF, 0xFEFF),
+    (0xFFA0, 0xFFA0), (0xFFF0, 0xFFF8), (0x1BCA0, 0x1BCA3), (0x1D173, 0x1D17A),
+    (0xE0000, 0xE0FFF),
+]
+
+# PropList.txt
+WHITE_SPACE = [
+    (0x0009, 0x000D), (0x0020, 0x0020), (0x0085, 0x0085), (0x00A0, 0x00A0),
+    (0x1680, 0x1680), (0x2000, 0x200A), (0x2028, 0x2029), (0x202F, 0x202F),
+    (0x205F, 0x205F), (0x3000, 0x3000),
+]
+
+# RFC 5892 §2.5
+IGNORABLE_BLOCKS = [(0x20D0, 0x20FF), (0x1D100, 0x1D1FF), (0x1D200, 0x1D24F)]
+
+# HangulSyllableType.txt, types L, V and T
+OLD_HANGUL_JAMO = [
+    (0x1100, 0x115F), (0xA960, 0xA97C), (0x1160, 0x11A7), (0xD7B0, 0xD7C6),
+    (0x11A8, 0x11FF), (0xD7CB, 0xD7FB),
+]
+
+LETTER_DIGITS = {"Ll", "Lu", "Lo", "Nd", "Lm", "Mn", "Mc"}
+
+
+def within(cp, ranges):
+    return any(start <= cp <= end for start, end in ranges)
+
+
+def is_noncharacter(cp):
+    return 0xFDD0 <= cp <= 0xFDEF or cp & 0xFFFE == 0xFFFE
+
+
+def is_unstable(c):
+    nfkc = unicodedata.normalize
+    return nfkc("NFKC", nfkc("NFKC", c).casefold()) != c
+
+
+# RFC 5892 §3
+def derived_property(cp):
+    c = chr(cp)
+
+    if cp in EXCEPTIONS:
+        return EXCEPTIONS[cp]
+    if unicodedata.category(c) == "Cn" and not is_noncharacter(cp):
+        return "UNASSIGNED"
+    if c.isascii() and (c.islower() or c.isdigit() or c == "-"):
+        return "PVALID"
+    if cp in (0x200C, 0x200D):
+        return "CONTEXTJ"
+    if is_unstable(c):
+        return "DISALLOWED"
+    if within(cp, DEFAULT_IGNORABLE) or within(cp, WHITE_SPACE) or is_noncharacter(cp):
+        return "DISALLOWED"
+    if within(cp, IGNORABLE_BLOCKS) or within(cp, OLD_HANGUL_JAMO):
+        return "DISALLOWED"
+    if unicodedata.category(c) in LETTER_DIGITS:
+        return "PVALID"
+    return "DISALLOWED"
+
+
+def ranges(code_points, value=lambda cp: None):
+    out = []
+
+    for cp in sorted(code_points):
+        if out and out[-1][1] == cp - 1 and out[-1][2] == value(cp):
+            out[-1][1] = cp
+        else:
+            out.append([cp, cp, value(cp)])
+
+    return out
+
+
+def decomposition(cp):
+    mapping = unicodedata.decomposition(chr(cp))
+
+    if not mapping or mapping.startswith("<"):
+        return None
+
+    return [int(part, 16) for part in mapping.split()]
+
+
+CODE_POINTS = [cp for cp in range(0x80, 0x110000) if not 0xD800 <= cp <= 0xDFFF]
+
+PROPERTIES = {cp: derived_property(cp) for cp in CODE_POINTS}
+
+# ASCII is checked separately, and labels needing the contextual rules are refused, apart from
+# those for MIDDLE DOT and the two sets of Arabic-Indic digits
+ALLOWED = {
+    cp for cp, value in PROPERTIES.items()
+    if value == "PVALID"
+    or cp == 0x00B7
+    or 0x0660 <= cp <= 0x0669
+    or 0x06F0 <= cp <= 0x06F9
+}
+
+MARKS = [
+    (start, end)
+    for start, end, _ in ranges(
+        cp for cp in ALLOWED if unicodedata.category(chr(cp)) in ("Mn", "Mc")
+    )
+]
+
+# Canonical decompositions of the allowed characters into two, which NFC composes back together.
+# Hangul syllables are left out, as the conjoining jamo they're made from are disallowed.
+DECOMPOSITIONS = sorted(
+    (cp, parts[0], parts[1])
+    for cp in sorted(ALLOWED)
+    if not 0xAC00 <= cp <= 0xD7A3
+    for parts in [decomposition(cp)]
+    if parts and len(parts) == 2
+)
+
+COMPOSITIONS = sorted((first, second, cp) for cp, first, second in DECOMPOSITIONS)
+
+COMBINING_CLASSES = ranges(
+    (
+        cp
+        for cp in ALLOWED | {part for _, first, second in DECOMPOSITIONS for part in (first, second)}
+        if unicodedata.combining(chr(cp))
+    ),
+    lambda cp: unicodedata.combining(chr(cp)),
+)
+
+CLASSES = {
+    cp: value for start, end, value in COMBINING_CLASSES for cp in range(start, end + 1)
+}
+
+
+# The same steps as is_normalized in src/idna.rs, run here against Python's normalization
+def is_normalized(label):
+    decompositions = {cp: (first, second) for cp, first, second in DECOMPOSITIONS}
+    compositions = {(first, second): cp for first, second, cp in COMPOSITIONS}
+    combining = CLASSES.get
+
+    def decompose(cp, out):
+        if cp in decompositions:
+            for part in decompositions[cp]:
+                decompose(part, out)
+        else:
+            out.append(cp)
+
+    decomposed = []
+    for c in label:
+        decompose(ord(c), decomposed)
+
+    # Canonical ordering, by swapping marks until none is after one with a higher class
+    swapped = True
+    while swapped:
+        swapped = False
+        for i in range(1, len(decomposed)):
+            before, after = combining(decomposed[i - 1], 0), combining(decomposed[i], 0)
+            if after != 0 and before > after:
+                decomposed[i - 1], decomposed[i] = decomposed[i], decomposed[i - 1]
+                swapped = True
+
+    composed = []
+    starter = None
+    last_class = 0
+
+    for cp in decomposed:
+        class_ = combining(cp, 0)
+        # Marks in between block a composition unless their class is lower, and a starter after
+        # the last one only composes with it when they're next to each other
+        adjacent = starter == len(composed) - 1
+        unblocked = starter is not None and (last_class < class_ or adjacent)
+
+        if unblocked and (composed[starter], cp) in compositions:
+            composed[starter] = compositions[(composed[starter], cp)]
+            continue
+
+        if class_ == 0:
+            starter = len(composed)
+
+        last_class = class_
+        composed.append(cp)
+
+    return composed == [ord(c) for c in label]
+
+
+def check():
+    marks = [chr(cp) for start, end, _ in COMBINING_CLASSES for cp in range(start, end + 1)]
+    composites = [chr(cp) for cp, _, _ in DECOMPOSITIONS]
+    parts = [chr(part) for _, first, second in DECOMPOSITIONS for part in (first, second)]
+    pool = marks + composites + parts + list("abcdefghij")
+
+    rng = random.Random(0)
+
+    for _ in range(200000):
+        label = "".join(rng.choice(pool) for _ in range(rng.randint(1, 5)))
+        assert is_normalized(label) == unicodedata.is_normalized("NFC", label), ascii(label)
+
+
+def rust_ranges(name, doc, items, formatter, per_line):
+    lines = ["/// " + line for line in doc]
+    lines.append("#[rustfmt::skip]")
+    lines.append("pub(crate) const {}: &[{}] = &[".format(name, formatter[0]))
+
+    items = [formatter[1](item) for item in items]
+
+    for i in range(0, len(items), per_line):
+        lines.append("    " + ", ".join(items[i:i + per_line]) + ",")
+
+    lines.append("];")
+    return "\n".join(lines)
+
+
+def main():
+    assert unicodedata.unidata_version == UNICODE_VERSION, unicodedata.unidata_version
+    check()
+
+    pair = ("(u32, u32)", lambda p: "(0x{:04X}, 0x{:04X})".format(*p))
+    triple = ("(u32, u32, u32)", lambda t: "(0x{:04X}, 0x{:04X}, 0x{:04X})".format(*t))
+    classed = ("(u32, u32, u8)", lambda r: "(0x{:04X}, 0x{:04X}, {})".format(*r))
+
+    print("// Generated by scripts/idna_tables.py from the Unicode {} character database. Don't edit"
+          .format(UNICODE_VERSION))
+    print("// it by hand; run the script again instead.")
+    print()
+    print(rust_ranges(
+        "ALLOWED",
+        ["Ranges of non-ASCII characters that may appear in a label: those IDNA2008 (RFC 5892)",
+         "makes PVALID, along with MIDDLE DOT and the Arabic-Indic digits whose rules are checked."],
+        [(start, end) for start, end, _ in ranges(ALLOWED)],
+        pair,
+        4,
+    ))
+    print()
+    print(rust_ranges(
+        "MARKS",
+        ["Ranges of the allowed characters that are combining marks, which can't start a label."],
+        MARKS,
+        pair,
+        4,
+    ))
+    print()
+    print(rust_ranges(
+        "DECOMPOSITIONS",
+        ["Canonical decompositions of allowed characters into two others, sorted by the character."],
+        DECOMPOSITIONS,
+        triple,
+        3,
+    ))
+    print()
+    print(rust_ranges(
+        "COMPOSITIONS",
+        ["The same pairs sorted by what they decompose into, for NFC to compose them again."],
+        COMPOSITIONS,
+        triple,
+        3,
+    ))
+    print()
+    print(rust_ranges(
+        "COMBINING_CLASSES",
+        ["Ranges of characters with the same nonzero canonical combining class."],
+        COMBINING_CLASSES,
+        classed,
+        3,
+    ))
+
+if __name__ == "__main__":
+    main()
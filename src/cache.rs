@@ -0,0 +1,343 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
+
+use crate::rdata::RData;
+use crate::types::{
+    Answer, DomainName, Message, Question, ResourceClass, ResourceType, ResponseCode,
+};
+
+type CacheKey = (DomainName, ResourceType, ResourceClass);
+
+#[derive(Debug)]
+struct CacheEntry {
+    inserted: Instant,
+    time_to_live: u32,
+    last_used: u64,
+    response: CachedResponse,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub response_code: ResponseCode,
+    pub answers: Vec<Answer>,
+    pub authorities: Vec<Answer>,
+    pub additionals: Vec<Answer>,
+}
+
+/// Caches upstream responses per question until their TTL runs out, evicting the least recently
+/// used entry once `capacity` is reached.
+#[derive(Debug)]
+pub struct Cache {
+    capacity: usize,
+    entries: HashMap<CacheKey, CacheEntry>,
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+impl Cache {
+    pub fn new(capacity: usize) -> Self {
+        Cache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the cached response for `question` with every TTL reduced by the time spent in the
+    /// cache.
+    pub fn get(&mut self, question: &Question) -> Option<CachedResponse> {
+        let key = Self::key(question);
+        let entry = self.entries.get_mut(&key)?;
+
+        let elapsed = entry.inserted.elapsed().as_secs();
+
+        if elapsed >= entry.time_to_live as u64 {
+            let last_used = entry.last_used;
+
+            self.entries.remove(&key);
+            self.recency.remove(&last_used);
+
+            return None;
+        }
+
+        self.tick += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.tick, key);
+        entry.last_used = self.tick;
+
+        let elapsed = elapsed as u32;
+        let mut response = entry.response.clone();
+
+        for record in response
+            .answers
+            .iter_mut()
+            .chain(&mut response.authorities)
+            .chain(&mut response.additionals)
+        {
+            record.time_to_live = record.time_to_live.saturating_sub(elapsed);
+        }
+
+        Some(response)
+    }
+
+    pub fn insert(&mut self, question: &Question, response: &Message) {
+        if response.header.truncation || self.capacity == 0 {
+            return;
+        }
+
+        let Some(time_to_live) = Self::time_to_live(response) else {
+            return;
+        };
+
+        if time_to_live == 0 {
+            return;
+        }
+
+        let mut authorities = response.authorities.clone();
+
+        // RFC 2308 §3: the SOA of a negative answer is cached for the lesser of its TTL and
+        // MINIMUM, so that is what it counts down from
+        if response.answers.is_empty() {
+            for record in &mut authorities {
+                if let RData::SOA { minimum, .. } = record.data {
+                    record.time_to_live = record.time_to_live.min(minimum);
+                }
+            }
+        }
+
+        let key = Self::key(question);
+
+        if let Some(previous) = self.entries.remove(&key) {
+            self.recency.remove(&previous.last_used);
+        }
+
+        while self.entries.len() >= self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+
+            self.entries.remove(&oldest);
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            CacheEntry {
+                inserted: Instant::now(),
+                time_to_live,
+                last_used: self.tick,
+                response: CachedResponse {
+                    response_code: response.header.response_code,
+                    answers: response.answers.clone(),
+                    authorities,
+                    additionals: response.additionals.clone(),
+                },
+            },
+        );
+    }
+
+    fn key(question: &Question) -> CacheKey {
+        (
+            question.name.clone(),
+            question.question_type,
+            question.question_class,
+        )
+    }
+
+    /// Works out how long a response may be cached for, if at all. Negative responses are cached
+    /// for the lesser of the SOA TTL and its MINIMUM field (RFC 2308 §5), and only when the SOA is
+    /// present.
+    fn time_to_live(response: &Message) -> Option<u32> {
+        let negative_ttl = || {
+            response
+                .authorities
+                .iter()
+                .find_map(|record| match record.data {
+                    RData::SOA { minimum, .. } => Some(record.time_to_live.min(minimum)),
+                    _ => None,
+                })
+        };
+
+        match response.header.response_code {
+            ResponseCode::NoError if !response.answers.is_empty() => response
+                .answers
+                .iter()
+                .map(|record| record.time_to_live)
+                .min(),
+            ResponseCode::NoError | ResponseCode::NameError => negative_ttl(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    use super::*;
+
+    fn question(name: &str) -> Question {
+        Question {
+            name: name.parse().unwrap(),
+            question_type: ResourceType::A,
+            question_class: ResourceClass::IN,
+        }
+    }
+
+    fn record(name: &str, time_to_live: u32) -> Answer {
+        Answer {
+            name: name.parse().unwrap(),
+            resource_type: ResourceType::A,
+            class: ResourceClass::IN,
+            time_to_live,
+            data: RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        }
+    }
+
+    fn soa(time_to_live: u32, minimum: u32) -> Answer {
+        Answer {
+            name: "example.".parse().unwrap(),
+            resource_type: ResourceType::SOA,
+            class: ResourceClass::IN,
+            time_to_live,
+            data: RData::SOA {
+                mname: "ns.example.".parse().unwrap(),
+                rname: "admin.example.".parse().unwrap(),
+                serial: 1,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1209600,
+                minimum,
+            },
+        }
+    }
+
+    fn response(answers: Vec<Answer>) -> Message {
+        Message::builder().response(true).answers(answers).build()
+    }
+
+    fn negative_response(response_code: ResponseCode, authorities: Vec<Answer>) -> Message {
+        Message::builder()
+            .response(true)
+            .response_code(response_code)
+            .authorities(authorities)
+            .build()
+    }
+
+    // Pretends the entry was cached `seconds` earlier than it was
+    fn age(cache: &mut Cache, question: &Question, seconds: u64) {
+        let entry = cache.entries.get_mut(&Cache::key(question)).unwrap();
+        entry.inserted = entry
+            .inserted
+            .checked_sub(Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn ttls_count_down_until_the_shortest_runs_out() {
+        let mut cache = Cache::new(8);
+        let question = question("example.");
+
+        cache.insert(
+            &question,
+            &response(vec![record("example.", 300), record("example.", 60)]),
+        );
+        age(&mut cache, &question, 10);
+
+        let cached = cache.get(&question).unwrap();
+        let ttls: Vec<u32> = cached
+            .answers
+            .iter()
+            .map(|record| record.time_to_live)
+            .collect();
+
+        assert_eq!(cached.response_code, ResponseCode::NoError);
+        assert_eq!(ttls, [290, 50]);
+
+        age(&mut cache, &question, 50);
+
+        assert!(cache.get(&question).is_none());
+        assert!(cache.entries.is_empty());
+        assert!(cache.recency.is_empty());
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_first() {
+        let mut cache = Cache::new(2);
+        let [a, b, c] = [question("a."), question("b."), question("c.")];
+
+        cache.insert(&a, &response(vec![record("a.", 60)]));
+        cache.insert(&b, &response(vec![record("b.", 60)]));
+
+        // Using a makes b the oldest
+        assert!(cache.get(&a).is_some());
+        cache.insert(&c, &response(vec![record("c.", 60)]));
+
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+
+        // Replacing an entry doesn't evict anything else
+        cache.insert(&c, &response(vec![record("c.", 120)]));
+
+        assert!(cache.get(&a).is_some());
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(cache.recency.len(), 2);
+    }
+
+    #[test]
+    fn negative_answers_last_for_the_lesser_of_soa_ttl_and_minimum() {
+        for (response_code, soa_ttl, minimum, expected) in [
+            (ResponseCode::NameError, 3600, 300, 300),
+            (ResponseCode::NameError, 100, 300, 100),
+            (ResponseCode::NoError, 3600, 300, 300),
+        ] {
+            let mut cache = Cache::new(8);
+            let question = question("missing.example.");
+
+            cache.insert(
+                &question,
+                &negative_response(response_code, vec![soa(soa_ttl, minimum)]),
+            );
+
+            let cached = cache.get(&question).unwrap();
+            assert_eq!(cached.authorities[0].time_to_live, expected);
+
+            age(&mut cache, &question, expected as u64 - 1);
+
+            let cached = cache.get(&question).unwrap();
+            assert_eq!(cached.response_code, response_code);
+            assert_eq!(cached.authorities[0].time_to_live, 1);
+
+            age(&mut cache, &question, 1);
+            assert!(cache.get(&question).is_none());
+        }
+    }
+
+    #[test]
+    fn uncacheable_responses_are_skipped() {
+        let mut cache = Cache::new(8);
+        let question = question("example.");
+
+        let mut truncated = response(vec![record("example.", 60)]);
+        truncated.header.truncation = true;
+
+        for response in [
+            truncated,
+            response(vec![record("example.", 0)]),
+            negative_response(ResponseCode::NameError, vec![]),
+            negative_response(ResponseCode::ServerFailure, vec![soa(60, 60)]),
+        ] {
+            cache.insert(&question, &response);
+            assert!(cache.get(&question).is_none());
+        }
+
+        let mut disabled = Cache::new(0);
+        disabled.insert(&question, &response(vec![record("example.", 60)]));
+        assert!(disabled.get(&question).is_none());
+    }
+}
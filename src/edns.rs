@@ -0,0 +1,151 @@
+use std::fmt;
+
+use nom::{
+    combinator::{eof, map},
+    multi::many_till,
+    number::complete::be_u16,
+    sequence::tuple,
+};
+
+use crate::error::{EncodeError, WireError};
+use crate::parse::{self, length_data, ParseResult};
+use crate::rdata::RData;
+use crate::types::{Answer, DomainName, ResourceClass, ResourceType, ResponseCode};
+
+/// The largest UDP payload we advertise or accept, following the DNS flag day 2020 recommendation
+/// to avoid IP fragmentation.
+pub const MAX_UDP_PAYLOAD: u16 = 1232;
+
+// RFC 6891 §6.2.5: sizes below 512 must be treated as 512
+const MIN_UDP_PAYLOAD: u16 = 512;
+
+const DNSSEC_OK: u32 = 1 << 15;
+
+/// The contents of an OPT pseudo-record (RFC 6891).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edns {
+    pub udp_payload_size: u16,
+    pub version: u8,
+    pub dnssec_ok: bool,
+    pub options: Vec<EdnsOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdnsOption {
+    pub code: u16,
+    pub data: Vec<u8>,
+}
+
+impl Edns {
+    pub fn new(udp_payload_size: u16) -> Self {
+        Edns {
+            udp_payload_size,
+            version: 0,
+            dnssec_ok: false,
+            options: vec![],
+        }
+    }
+
+    /// Builds the OPT record to send back to a client that used EDNS. The DO bit has to be
+    /// copied from the query (RFC 3225 §3), but options are only echoed when we understand them.
+    pub fn response_to(query: &Edns) -> Self {
+        Edns {
+            dnssec_ok: query.dnssec_ok,
+            ..Edns::new(MAX_UDP_PAYLOAD)
+        }
+    }
+
+    /// The largest response the client can accept over UDP.
+    pub fn max_response_size(&self) -> usize {
+        self.udp_payload_size
+            .clamp(MIN_UDP_PAYLOAD, MAX_UDP_PAYLOAD) as usize
+    }
+
+    /// Builds the OPT record for a message, which carries the upper bits of its response code.
+    /// Fails if an option has more data than its 16-bit length can describe.
+    pub fn to_record(&self, response_code: ResponseCode) -> Result<Answer, EncodeError> {
+        let mut data = Vec::new();
+
+        for option in &self.options {
+            let length =
+                u16::try_from(option.data.len()).map_err(|_| EncodeError::OptionTooLong {
+                    length: option.data.len(),
+                })?;
+
+            data.extend_from_slice(&option.code.to_be_bytes());
+            data.extend_from_slice(&length.to_be_bytes());
+            data.extend_from_slice(&option.data);
+        }
+
+        Ok(Answer {
+            name: DomainName::root(),
+            resource_type: ResourceType::OPT,
+            class: ResourceClass::from(self.udp_payload_size),
+            time_to_live: (response_code.extended_bits() as u32) << 24
+                | (self.version as u32) << 16
+                | if self.dnssec_ok { DNSSEC_OK } else { 0 },
+            data: RData::Unknown(ResourceType::OPT, data),
+        })
+    }
+}
+
+impl fmt::Display for Edns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "; EDNS: version: {}, flags:{}; udp: {}",
+            self.version,
+            if self.dnssec_ok { " do" } else { "" },
+            self.udp_payload_size
+        )?;
+
+        for option in &self.options {
+            write!(f, "\n; OPT={}: ", option.code)?;
+            option
+                .data
+                .iter()
+                .try_for_each(|byte| write!(f, "{:02x}", byte))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The upper bits of the response code carried in an OPT record.
+pub fn extended_response_code(record: &Answer) -> u8 {
+    (record.time_to_live >> 24) as u8
+}
+
+impl TryFrom<&Answer> for Edns {
+    type Error = WireError;
+
+    fn try_from(record: &Answer) -> Result<Self, Self::Error> {
+        let RData::Unknown(ResourceType::OPT, data) = &record.data else {
+            return Err(WireError::InvalidOpt);
+        };
+
+        if !record.name.is_root() {
+            return Err(WireError::InvalidOpt);
+        }
+
+        let (_, (options, _)) =
+            parse::run(data, data, many_till(option, eof)).map_err(|_| WireError::InvalidOpt)?;
+
+        Ok(Edns {
+            udp_payload_size: u16::from(record.class),
+            version: (record.time_to_live >> 16) as u8,
+            dnssec_ok: record.time_to_live & DNSSEC_OK != 0,
+            options,
+        })
+    }
+}
+
+fn option(input: &[u8]) -> ParseResult<'_, EdnsOption> {
+    map(
+        tuple((be_u16, length_data(be_u16))),
+        |(code, data): (u16, &[u8])| EdnsOption {
+            code,
+            data: data.to_vec(),
+        },
+    )(input)
+}
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum WireError {
+    #[error("message is {length} bytes long, shorter than the 12 byte header")]
+    TruncatedHeader { length: usize },
+    #[error("unexpected end of message at offset {offset}")]
+    UnexpectedEnd { offset: usize },
+    #[error("invalid label length byte {length:#04x} at offset {offset}")]
+    BadLabelLength { offset: usize, length: u8 },
+    #[error("domain name at offset {offset} is longer than 255 bytes")]
+    NameTooLong { offset: usize },
+    #[error("compression pointer at offset {offset} points outside the message to {target}")]
+    PointerOutOfBounds { offset: usize, target: usize },
+    #[error("compression pointer at offset {offset} points into the header at {target}")]
+    PointerIntoHeader { offset: usize, target: usize },
+    #[error("compression pointer at offset {offset} points forward to {target}")]
+    ForwardPointer { offset: usize, target: usize },
+    #[error("compression pointers loop when reading the name at offset {offset}")]
+    PointerLoop { offset: usize },
+    #[error("RDATA at offset {offset} is not valid for its record type")]
+    InvalidRData { offset: usize },
+    #[error("RDATA at offset {offset} is {length} bytes long but the record only used {consumed}")]
+    RDataLengthMismatch {
+        offset: usize,
+        length: u16,
+        consumed: usize,
+    },
+    #[error("OPT record is malformed")]
+    InvalidOpt,
+    #[error("second OPT record at offset {offset}")]
+    DuplicateOpt { offset: usize },
+    #[error("{remaining} trailing bytes after the end of the message at offset {offset}")]
+    TrailingBytes { offset: usize, remaining: usize },
+    #[error("query has {count} questions instead of one")]
+    QuestionCount { count: u16 },
+    #[error("reserved Z bit is set")]
+    ReservedBitSet,
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    #[error("message is longer than {max_size} bytes")]
+    TooLong { max_size: usize },
+    #[error("{count} entries don't fit in a section")]
+    TooManyEntries { count: usize },
+    #[error("{length} bytes of RDATA don't fit in a record")]
+    RDataTooLong { length: usize },
+    #[error("{length} bytes don't fit in a character-string")]
+    CharacterStringTooLong { length: usize },
+    #[error("{length} bytes of data don't fit in an EDNS option")]
+    OptionTooLong { length: usize },
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("unknown mnemonic {0}")]
+pub struct UnknownMnemonic(pub String);
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum InvalidName {
+    #[error("name contains an empty label")]
+    EmptyLabel,
+    #[error("label is longer than 63 bytes")]
+    LabelTooLong,
+    #[error("name is longer than 255 bytes")]
+    NameTooLong,
+    #[error("invalid escape sequence")]
+    BadEscape,
+    #[error("label can't be converted to an internationalized domain name")]
+    InvalidIdna,
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("invalid record: {0}")]
+pub struct InvalidRecord(pub String);
+
+#[cfg(feature = "json")]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum JsonError {
+    #[error("invalid JSON at offset {offset}: {message}")]
+    Syntax { offset: usize, message: String },
+    #[error("missing member {0}")]
+    MissingMember(String),
+    #[error("invalid value for member {0}")]
+    InvalidMember(String),
+    #[error("invalid hex string")]
+    InvalidHex,
+    #[error(transparent)]
+    Wire(#[from] WireError),
+}
+
+#[derive(Debug, Error)]
+pub enum ZoneError {
+    #[error("unable to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{}:{line}: {message}", path.display())]
+    Syntax {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("{}: zone must contain exactly one SOA record at its apex", path.display())]
+    MissingSoa { path: PathBuf },
+}
+
+#[derive(Debug, Error)]
+pub enum UpstreamError {
+    #[error("unable to send to the resolver: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("timed out waiting for the resolver")]
+    Timeout,
+    #[error("invalid response from the resolver: {0}")]
+    Malformed(#[from] WireError),
+    #[error("resolver response did not match the query")]
+    Mismatch,
+}
@@ -0,0 +1,512 @@
+// Converts between Unicode labels and their ASCII `xn--` form using punycode (RFC 3492). Of the
+// UTS #46 mapping, case folding, fullwidth ASCII, the alternative full stops and dropping ignored
+// characters are done. Any other character that needs mapping, or that IDNA2008 disallows or
+// leaves unassigned, is rejected rather than encoded as some other name, and so are labels that
+// aren't in NFC. Contextual rules are only checked for MIDDLE DOT and the Arabic-Indic digits;
+// labels needing the others, or the bidi rules of RFC 5893, are rejected or not checked
+// respectively. The tables in idna_tables.rs are generated by scripts/idna_tables.py.
+
+use std::cmp::Ordering;
+
+use crate::idna_tables::{ALLOWED, COMBINING_CLASSES, COMPOSITIONS, DECOMPOSITIONS, MARKS};
+
+const ACE_PREFIX: &str = "xn--";
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 0x80;
+
+/// Characters UTS #46 maps to `.` when splitting a name into labels.
+pub fn is_label_separator(c: char) -> bool {
+    matches!(c, '.' | '\u{3002}' | '\u{FF0E}' | '\u{FF61}')
+}
+
+/// Maps a label and converts it to its ASCII form, which is left alone if it is already ASCII.
+/// Returns `None` if the label is empty once mapped or needs mapping that isn't supported.
+pub fn to_ascii(label: &str) -> Option<String> {
+    let mut mapped = String::new();
+
+    for c in label.chars().filter(|&c| !is_ignored(c)) {
+        let c = match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0)?,
+            _ => c,
+        };
+
+        mapped.extend(c.to_lowercase());
+    }
+
+    if mapped.is_empty() || mapped.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return None;
+    }
+
+    if mapped.is_ascii() {
+        return Some(mapped);
+    }
+
+    if !is_valid(&mapped) {
+        return None;
+    }
+
+    Some(format!("{}{}", ACE_PREFIX, encode(&mapped)?))
+}
+
+/// Converts an `xn--` label back to Unicode. Anything else, including labels that don't decode,
+/// is returned as `None` so the caller can keep the ASCII form.
+pub fn to_unicode(label: &str) -> Option<String> {
+    label
+        .get(..ACE_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(ACE_PREFIX))?;
+
+    let label = label.to_ascii_lowercase();
+    let decoded = decode(&label[ACE_PREFIX.len()..])?;
+
+    // Only accept what to_ascii would have produced, so each Unicode label has exactly one ASCII
+    // form and nothing decodes to an empty or plain ASCII label
+    (to_ascii(&decoded)? == label).then_some(decoded)
+}
+
+// The characters UTS #46 drops from a label
+fn is_ignored(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{180B}'..='\u{180D}'
+            | '\u{180F}'
+            | '\u{200B}'
+            | '\u{2060}'
+            | '\u{2064}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{1BCA0}'..='\u{1BCA3}'
+            | '\u{E0100}'..='\u{E01EF}'
+    )
+}
+
+// RFC 5891 §4.2: every character has to be allowed, the label can't start with a combining mark,
+// and it has to be in NFC
+fn is_valid(label: &str) -> bool {
+    let chars: Vec<char> = label.chars().collect();
+
+    if in_ranges(MARKS, chars[0]) {
+        return false;
+    }
+
+    for (i, &c) in chars.iter().enumerate() {
+        let allowed = match c {
+            'a'..='z' | '0'..='9' | '-' => true,
+            _ if c.is_ascii() => false,
+            // RFC 5892 Appendix A.3: only between two l's, as in Catalan
+            '\u{00B7}' => i > 0 && chars[i - 1] == 'l' && chars.get(i + 1) == Some(&'l'),
+            // RFC 5892 Appendices A.8 and A.9: the two sets of Arabic-Indic digits can't be mixed
+            '\u{0660}'..='\u{0669}' => !chars.iter().any(|c| ('\u{06F0}'..='\u{06F9}').contains(c)),
+            '\u{06F0}'..='\u{06F9}' => !chars.iter().any(|c| ('\u{0660}'..='\u{0669}').contains(c)),
+            _ => in_ranges(ALLOWED, c),
+        };
+
+        if !allowed {
+            return false;
+        }
+    }
+
+    is_normalized(&chars)
+}
+
+// Puts the label through NFC (UAX #15) to see whether that changes it
+fn is_normalized(label: &[char]) -> bool {
+    let mut decomposed = Vec::new();
+
+    for &c in label {
+        decompose(c as u32, &mut decomposed);
+    }
+
+    // Canonical ordering: no mark may follow one with a higher combining class
+    for end in (1..decomposed.len()).rev() {
+        for i in 0..end {
+            let (before, after) = (
+                combining_class(decomposed[i]),
+                combining_class(decomposed[i + 1]),
+            );
+
+            if after != 0 && before > after {
+                decomposed.swap(i, i + 1);
+            }
+        }
+    }
+
+    let mut composed: Vec<u32> = Vec::new();
+    let mut starter = None;
+    let mut last_class = 0;
+
+    for c in decomposed {
+        let class = combining_class(c);
+
+        // Marks in between block a composition unless their class is lower, and a starter after
+        // the last one only composes with it when they're next to each other
+        if let Some(starter) = starter {
+            let adjacent = starter == composed.len() - 1;
+
+            if last_class < class || adjacent {
+                if let Some(composite) = compose(composed[starter], c) {
+                    composed[starter] = composite;
+                    continue;
+                }
+            }
+        }
+
+        if class == 0 {
+            starter = Some(composed.len());
+        }
+
+        last_class = class;
+        composed.push(c);
+    }
+
+    composed.into_iter().eq(label.iter().map(|&c| c as u32))
+}
+
+fn decompose(c: u32, out: &mut Vec<u32>) {
+    match DECOMPOSITIONS.binary_search_by_key(&c, |&(composite, _, _)| composite) {
+        Ok(index) => {
+            let (_, first, second) = DECOMPOSITIONS[index];
+
+            decompose(first, out);
+            decompose(second, out);
+        }
+        Err(_) => out.push(c),
+    }
+}
+
+fn compose(first: u32, second: u32) -> Option<u32> {
+    let index = COMPOSITIONS
+        .binary_search_by_key(&(first, second), |&(first, second, _)| (first, second))
+        .ok()?;
+
+    Some(COMPOSITIONS[index].2)
+}
+
+fn combining_class(c: u32) -> u8 {
+    COMBINING_CLASSES
+        .binary_search_by(|&(start, end, _)| compare_range(start, end, c))
+        .map_or(0, |index| COMBINING_CLASSES[index].2)
+}
+
+fn in_ranges(ranges: &[(u32, u32)], c: char) -> bool {
+    ranges
+        .binary_search_by(|&(start, end)| compare_range(start, end, c as u32))
+        .is_ok()
+}
+
+fn compare_range(start: u32, end: u32, c: u32) -> Ordering {
+    if end < c {
+        Ordering::Less
+    } else if start > c {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+fn adapt(mut delta: u32, points: u32, first_time: bool) -> u32 {
+    delta /= if first_time { DAMP } else { 2 };
+    delta += delta / points;
+
+    let mut k = 0;
+
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(digit: u32) -> char {
+    match digit {
+        0..=25 => (b'a' + digit as u8) as char,
+        _ => (b'0' + (digit - 26) as u8) as char,
+    }
+}
+
+fn decode_digit(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 26),
+        _ => None,
+    }
+}
+
+fn encode(input: &str) -> Option<String> {
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+
+    let mut output: String = input
+        .iter()
+        .filter(|&&c| c < INITIAL_N)
+        .map(|&c| c as u8 as char)
+        .collect();
+
+    let basic = output.len() as u32;
+    let mut handled = basic;
+
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+
+    while (handled as usize) < input.len() {
+        let m = input.iter().copied().filter(|&c| c >= n).min()?;
+
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+
+                loop {
+                    let t = threshold(k, bias);
+
+                    if q < t {
+                        break;
+                    }
+
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        n = n.checked_add(1)?;
+    }
+
+    Some(output)
+}
+
+fn decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(delimiter) => (&input[..delimiter], &input[delimiter + 1..]),
+        None => ("", input),
+    };
+
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut digits = extended.chars();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+
+    while digits.as_str() != "" {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+
+        loop {
+            let digit = decode_digit(digits.next()?)?;
+
+            i = i.checked_add(digit.checked_mul(w)?)?;
+
+            let t = threshold(k, bias);
+
+            if digit < t {
+                break;
+            }
+
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let length = output.len() as u32 + 1;
+
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+
+        if n < INITIAL_N {
+            return None;
+        }
+
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_3492_examples() {
+        for (unicode, punycode) in [
+            ("ليهمابتكلموشعربي؟", "egbpdaj6bu4bxfgehfvwxn"),
+            ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+            ("他們爲什麽不說中文", "ihqwctvzc91f659drss3x8bo0yb"),
+            ("Pročprostěnemluvíčesky", "Proprostnemluvesky-uyb24dma41a"),
+            ("למההםפשוטלאמדבריםעברית", "4dbcagdahymbxekheh6e0a7fei0b"),
+            (
+                "यहलोगहिन्दीक्योंनहींबोलसकतेहैं",
+                "i1baa7eci9glrd9b2ae1bj0hfcgg6iyaf8o0a1dig0cd",
+            ),
+            (
+                "なぜみんな日本語を話してくれないのか",
+                "n8jok5ay5dzabd5bym9f0cm5685rrjetr6pdxa",
+            ),
+            (
+                "세계의모든사람들이한국어를이해한다면얼마나좋을까",
+                "989aomsvi5e83db1d2a355cv1e0vak1dwrv93d5xbh15a0dt30a5jpsd879ccm6fea98c",
+            ),
+            (
+                "почемужеонинеговорятпорусски",
+                "b1abfaaepdrnnbgefbadotcwatmq2g4l",
+            ),
+            (
+                "PorquénopuedensimplementehablarenEspañol",
+                "PorqunopuedensimplementehablarenEspaol-fmd56a",
+            ),
+            (
+                "TạisaohọkhôngthểchỉnóitiếngViệt",
+                "TisaohkhngthchnitingVit-kjcr8268qyxafd2f1b9g",
+            ),
+            ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+            (
+                "安室奈美恵-with-SUPER-MONKEYS",
+                "-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n",
+            ),
+            (
+                "Hello-Another-Way-それぞれの場所",
+                "Hello-Another-Way--fc4qua05auwb3674vfr0b",
+            ),
+            ("ひとつ屋根の下2", "2-u9tlzr9756bt3uc0v"),
+            ("MajiでKoiする5秒前", "MajiKoi5-783gue6qz075azm5e"),
+            ("パフィーdeルンバ", "de-jg4avhby1noc0d"),
+            ("そのスピードで", "d9juau41awczczp"),
+            ("-> $1.00 <-", "-> $1.00 <--"),
+        ] {
+            assert_eq!(encode(unicode).as_deref(), Some(punycode));
+            assert_eq!(decode(punycode).as_deref(), Some(unicode));
+        }
+
+        // Decoders have to accept digits in either case
+        assert_eq!(
+            decode("b1abfaaepdrnnbgefbaDotcwatmq2g4l").as_deref(),
+            Some("почемужеонинеговорятпорусски")
+        );
+    }
+
+    #[test]
+    fn labels_are_mapped() {
+        for (label, ascii) in [
+            ("bücher", "xn--bcher-kva"),
+            ("BÜCHER", "xn--bcher-kva"),
+            ("bü\u{AD}cher", "xn--bcher-kva"),
+            ("ｅｘａｍｐｌｅ", "example"),
+            ("ＥＸＡＭＰＬＥ", "example"),
+            ("\u{2126}", "xn--bya"),
+            ("\u{0929}", "xn--m2b"),
+            ("가", "xn--o39a"),
+            ("ß", "xn--zca"),
+            ("ς", "xn--3xa"),
+            ("ệ", "xn--qlg"),
+            // Already in NFC even though the marks aren't in order of combining class
+            ("\u{0117}\u{3099}", "xn--lea834v"),
+            ("l\u{B7}l", "xn--ll-0ea"),
+            ("\u{0660}\u{0661}\u{0662}", "xn--8hbcd"),
+            ("\u{06F0}\u{06F1}\u{06F2}", "xn--dmbcd"),
+        ] {
+            assert_eq!(to_ascii(label).as_deref(), Some(ascii), "{}", label);
+        }
+    }
+
+    #[test]
+    fn labels_needing_unsupported_mapping_are_rejected() {
+        for label in [
+            "",
+            "\u{AD}",
+            // Decomposed, so NFC would change them
+            "bu\u{308}cher",
+            "e\u{0302}\u{0323}",
+            "e\u{0323}\u{0302}",
+            "\u{0928}\u{093C}",
+            "\u{1100}\u{1161}",
+            // Starting with a combining mark
+            "\u{0301}",
+            "\u{093F}\u{0928}",
+            // Compatibility characters
+            "\u{FB01}",
+            "\u{2460}",
+            "\u{FF76}",
+            "\u{1D400}",
+            // Joiners, private use, unassigned and noncharacters
+            "a\u{200D}b",
+            "\u{E000}",
+            "\u{0378}",
+            "\u{FDD0}",
+            "\u{10FFFF}",
+            // Symbols, punctuation and ASCII other than letters, digits and hyphens
+            "\u{FFFC}",
+            "\u{FFFD}",
+            "\u{2665}",
+            "\u{00A1}",
+            "bü_cher",
+            // Contextual rules that don't hold, or aren't checked
+            "a\u{B7}b",
+            "\u{B7}l",
+            "\u{0660}\u{06F0}",
+            "\u{03B1}\u{0375}\u{03B1}",
+        ] {
+            assert_eq!(to_ascii(label), None, "{:?}", label);
+        }
+    }
+
+    #[test]
+    fn only_canonical_labels_convert_to_unicode() {
+        assert_eq!(to_unicode("xn--bcher-kva").as_deref(), Some("bücher"));
+        assert_eq!(to_unicode("XN--BCHER-KVA").as_deref(), Some("bücher"));
+
+        for label in [
+            "example",
+            "xn--",
+            "xn--abc-",
+            "xn--bucher-xyd",
+            "xn--mi7chab1aes7c",
+            "xn--zz",
+        ] {
+            assert_eq!(to_unicode(label), None, "{}", label);
+        }
+    }
+}
@@ -0,0 +1,844 @@
+use std::fmt::Write;
+
+use crate::edns::{extended_response_code, Edns};
+use crate::error::{EncodeError, JsonError};
+use crate::rdata::RData;
+use crate::types::{
+    Answer, DomainName, Header, Message, MessageEncoder, OperationCode, Question, ResourceClass,
+    ResourceType, ResponseCode,
+};
+
+/// Writes a message as an RFC 8427 JSON object. When the octets the message was read from are
+/// given they are included as `messageOctetsHEX`, and [`decode_message`] hands them back as they
+/// were. Fails if some part of the message is too long for the wire format.
+pub fn encode_message(message: &Message, octets: Option<&[u8]>) -> Result<String, EncodeError> {
+    let header = &message.header;
+
+    let mut additionals: Vec<Answer> = message.additionals.clone();
+    if let Some(edns) = &message.edns {
+        additionals.push(edns.to_record(header.response_code)?);
+    }
+
+    let mut members = vec![
+        ("ID", Value::Number(header.id as u64)),
+        ("QR", Value::Bool(header.qr_indicator)),
+        ("Opcode", Value::Number(u8::from(header.op_code) as u64)),
+        ("AA", Value::Bool(header.authoritative_answer)),
+        ("TC", Value::Bool(header.truncation)),
+        ("RD", Value::Bool(header.recursion_desired)),
+        ("RA", Value::Bool(header.recursion_available)),
+        ("AD", Value::Bool(header.authentic_data)),
+        ("CD", Value::Bool(header.checking_disabled)),
+        (
+            "RCODE",
+            Value::Number(header.response_code.low_bits() as u64),
+        ),
+        ("QDCOUNT", Value::Number(message.questions.len() as u64)),
+        ("ANCOUNT", Value::Number(message.answers.len() as u64)),
+        ("NSCOUNT", Value::Number(message.authorities.len() as u64)),
+        ("ARCOUNT", Value::Number(additionals.len() as u64)),
+        (
+            "questionRRs",
+            Value::Array(message.questions.iter().map(encode_question).collect()),
+        ),
+        (
+            "answerRRs",
+            Value::Array(
+                message
+                    .answers
+                    .iter()
+                    .map(encode_record)
+                    .collect::<Result<_, _>>()?,
+            ),
+        ),
+        (
+            "authorityRRs",
+            Value::Array(
+                message
+                    .authorities
+                    .iter()
+                    .map(encode_record)
+                    .collect::<Result<_, _>>()?,
+            ),
+        ),
+        (
+            "additionalRRs",
+            Value::Array(
+                additionals
+                    .iter()
+                    .map(encode_record)
+                    .collect::<Result<_, _>>()?,
+            ),
+        ),
+    ];
+
+    if let Some(octets) = octets {
+        members.push(("messageOctetsHEX", Value::String(hex(octets))));
+    }
+
+    let mut out = String::new();
+    object(members).write(&mut out);
+
+    Ok(out)
+}
+
+/// Reads a message from an RFC 8427 JSON object. When `messageOctetsHEX` is present the message
+/// is decoded from it, and the octets are returned too, since encoding the message again needn't
+/// give back the same bytes. Otherwise the message is rebuilt from the structured members.
+pub fn decode_message(text: &str) -> Result<(Message, Option<Vec<u8>>), JsonError> {
+    let value = Parser::new(text).parse_document()?;
+
+    if let Some(octets) = value.get("messageOctetsHEX") {
+        let octets = unhex(octets.as_str("messageOctetsHEX")?)?;
+
+        return Ok((Message::try_from(octets.as_slice())?, Some(octets)));
+    }
+
+    let mut header = Header {
+        id: value.optional_number("ID")?,
+        qr_indicator: value.field_bool("QR")?,
+        op_code: OperationCode::from(value.optional_number::<u8>("Opcode")?),
+        authoritative_answer: value.field_bool("AA")?,
+        truncation: value.field_bool("TC")?,
+        recursion_desired: value.field_bool("RD")?,
+        recursion_available: value.field_bool("RA")?,
+        authentic_data: value.field_bool("AD")?,
+        checking_disabled: value.field_bool("CD")?,
+        response_code: ResponseCode::from(value.optional_number::<u16>("RCODE")?),
+        ..Header::default()
+    };
+
+    let questions = match value.get("questionRRs") {
+        Some(questions) => questions
+            .as_array("questionRRs")?
+            .iter()
+            .map(decode_question)
+            .collect::<Result<Vec<_>, _>>()?,
+        // A single question may be given inline instead
+        None if value.get("QNAME").is_some() => vec![Question {
+            name: value.field_name("QNAME")?,
+            question_type: ResourceType::from(value.field_number::<u16>("QTYPE")?),
+            question_class: ResourceClass::from(value.field_number::<u16>("QCLASS")?),
+        }],
+        None => vec![],
+    };
+
+    let records = |member: &str| match value.get(member) {
+        Some(records) => records
+            .as_array(member)?
+            .iter()
+            .map(decode_record)
+            .collect::<Result<Vec<_>, _>>(),
+        None => Ok(vec![]),
+    };
+
+    let answers = records("answerRRs")?;
+    let authorities = records("authorityRRs")?;
+    let mut additionals = Vec::new();
+    let mut edns = None;
+
+    for record in records("additionalRRs")? {
+        if record.resource_type != ResourceType::OPT {
+            additionals.push(record);
+            continue;
+        }
+
+        if edns.is_some() {
+            return Err(JsonError::InvalidMember("additionalRRs".to_string()));
+        }
+
+        edns = Some(Edns::try_from(&record)?);
+        header.response_code = ResponseCode::from_parts(
+            header.response_code.low_bits(),
+            extended_response_code(&record),
+        );
+    }
+
+    let message = Message::builder()
+        .id(header.id)
+        .response(header.qr_indicator)
+        .op_code(header.op_code)
+        .authoritative(header.authoritative_answer)
+        .truncated(header.truncation)
+        .recursion_desired(header.recursion_desired)
+        .recursion_available(header.recursion_available)
+        .authentic_data(header.authentic_data)
+        .checking_disabled(header.checking_disabled)
+        .response_code(header.response_code)
+        .questions(questions)
+        .answers(answers)
+        .authorities(authorities)
+        .additionals(additionals)
+        .edns(edns)
+        .build();
+
+    Ok((message, None))
+}
+
+fn encode_question(question: &Question) -> Value {
+    object(vec![
+        ("NAME", Value::String(question.name.to_string())),
+        (
+            "TYPE",
+            Value::Number(u16::from(question.question_type) as u64),
+        ),
+        (
+            "CLASS",
+            Value::Number(u16::from(question.question_class) as u64),
+        ),
+    ])
+}
+
+fn decode_question(value: &Value) -> Result<Question, JsonError> {
+    Ok(Question {
+        name: value.field_name("NAME")?,
+        question_type: ResourceType::from(value.field_number::<u16>("TYPE")?),
+        question_class: ResourceClass::from(value.field_number::<u16>("CLASS")?),
+    })
+}
+
+fn encode_record(record: &Answer) -> Result<Value, EncodeError> {
+    let mut rdata = Vec::new();
+    let mut encoder = MessageEncoder::new(&mut rdata, false, usize::MAX);
+    record.data.encode(&mut encoder);
+    encoder.finish()?;
+
+    if rdata.len() > u16::MAX as usize {
+        return Err(EncodeError::RDataTooLong {
+            length: rdata.len(),
+        });
+    }
+
+    let mut members = vec![
+        ("NAME", Value::String(record.name.to_string())),
+        (
+            "TYPE",
+            Value::Number(u16::from(record.resource_type) as u64),
+        ),
+        ("CLASS", Value::Number(u16::from(record.class) as u64)),
+        ("TTL", Value::Number(record.time_to_live as u64)),
+        ("RDLENGTH", Value::Number(rdata.len() as u64)),
+        ("RDATAHEX", Value::String(hex(&rdata))),
+    ];
+
+    // The presentation form is only there for people to read; RDATAHEX is what gets decoded.
+    // Types without a mnemonic have no rdata member name, so they get only the hex.
+    let presentation_name = format!("rdata{}", record.resource_type);
+    if !matches!(record.data, RData::Unknown(..)) {
+        members.push((&presentation_name, Value::String(record.data.to_string())));
+    }
+
+    Ok(object(members))
+}
+
+fn decode_record(value: &Value) -> Result<Answer, JsonError> {
+    let name = value.field_name("NAME")?;
+    let resource_type = ResourceType::from(value.field_number::<u16>("TYPE")?);
+    let class = ResourceClass::from(value.field_number::<u16>("CLASS")?);
+    let time_to_live = value.field_number("TTL")?;
+
+    let data = match value.get("RDATAHEX") {
+        Some(rdata) => {
+            let rdata = unhex(rdata.as_str("RDATAHEX")?)?;
+            let length = u16::try_from(rdata.len())
+                .map_err(|_| JsonError::InvalidMember("RDATAHEX".to_string()))?;
+
+            RData::parse(&rdata, 0, length, resource_type)?
+        }
+        None => {
+            let member = format!("rdata{}", resource_type);
+            let text = value
+                .get(&member)
+                .ok_or_else(|| JsonError::MissingMember("RDATAHEX".to_string()))?
+                .as_str(&member)?;
+
+            format!(
+                "{} {} {} {} {}",
+                name, time_to_live, class, resource_type, text
+            )
+            .parse::<Answer>()
+            .map_err(|_| JsonError::InvalidMember(member))?
+            .data
+        }
+    };
+
+    Ok(Answer {
+        name,
+        resource_type,
+        class,
+        time_to_live,
+        data,
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{:02X}", byte);
+        out
+    })
+}
+
+fn unhex(text: &str) -> Result<Vec<u8>, JsonError> {
+    if text.len() % 2 != 0 || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(JsonError::InvalidHex);
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(|_| JsonError::InvalidHex))
+        .collect()
+}
+
+fn object(members: Vec<(&str, Value)>) -> Value {
+    Value::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+// Just enough JSON for RFC 8427: every number in a message is a non-negative integer, and member
+// order is kept so output is stable.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find(|(member, _)| member == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self, name: &str) -> Result<&str, JsonError> {
+        match self {
+            Value::String(text) => Ok(text),
+            _ => Err(JsonError::InvalidMember(name.to_string())),
+        }
+    }
+
+    fn as_array(&self, name: &str) -> Result<&[Value], JsonError> {
+        match self {
+            Value::Array(values) => Ok(values),
+            _ => Err(JsonError::InvalidMember(name.to_string())),
+        }
+    }
+
+    fn field(&self, name: &str) -> Result<&Value, JsonError> {
+        self.get(name)
+            .ok_or_else(|| JsonError::MissingMember(name.to_string()))
+    }
+
+    fn field_number<T: TryFrom<u64>>(&self, name: &str) -> Result<T, JsonError> {
+        match self.field(name)? {
+            Value::Number(number) => {
+                T::try_from(*number).map_err(|_| JsonError::InvalidMember(name.to_string()))
+            }
+            _ => Err(JsonError::InvalidMember(name.to_string())),
+        }
+    }
+
+    // Header fields that are left out are taken to be zero
+    fn optional_number<T: TryFrom<u64> + Default>(&self, name: &str) -> Result<T, JsonError> {
+        match self.get(name) {
+            None => Ok(T::default()),
+            Some(_) => self.field_number(name),
+        }
+    }
+
+    // Flags that are left out are taken to be clear
+    fn field_bool(&self, name: &str) -> Result<bool, JsonError> {
+        match self.get(name) {
+            None => Ok(false),
+            Some(Value::Bool(value)) => Ok(*value),
+            Some(Value::Number(value @ (0 | 1))) => Ok(*value == 1),
+            Some(_) => Err(JsonError::InvalidMember(name.to_string())),
+        }
+    }
+
+    fn field_name(&self, name: &str) -> Result<DomainName, JsonError> {
+        self.field(name)?
+            .as_str(name)?
+            .parse()
+            .map_err(|_| JsonError::InvalidMember(name.to_string()))
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Value::Number(value) => {
+                let _ = write!(out, "{}", value);
+            }
+            Value::String(text) => write_string(text, out),
+            Value::Array(values) => {
+                out.push('[');
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    value.write(out);
+                }
+
+                out.push(']');
+            }
+            Value::Object(members) => {
+                out.push('{');
+
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_string(name, out);
+                    out.push(':');
+                    value.write(out);
+                }
+
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+/// Far deeper than any message needs, but shallow enough that nesting can't run out of stack.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    text: &'a str,
+    offset: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser {
+            text,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Value, JsonError> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+
+        if self.offset != self.text.len() {
+            return Err(self.error("trailing characters after the document"));
+        }
+
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> JsonError {
+        JsonError::Syntax {
+            offset: self.offset,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.offset += 1;
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str, value: Value) -> Result<Value, JsonError> {
+        if !self.text[self.offset..].starts_with(literal) {
+            return Err(self.error("unexpected character"));
+        }
+
+        self.offset += literal.len();
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(c @ ('{' | '[')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("nested too deeply"));
+                }
+
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.parse_object()
+                } else {
+                    self.parse_array()
+                };
+                self.depth -= 1;
+
+                value
+            }
+            Some('"') => Ok(Value::String(self.parse_string()?)),
+            Some('t') => self.expect_literal("true", Value::Bool(true)),
+            Some('f') => self.expect_literal("false", Value::Bool(false)),
+            Some('n') => self.expect_literal("null", Value::Null),
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.offset;
+
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.offset += 1;
+                }
+
+                self.text[start..self.offset]
+                    .parse()
+                    .map(Value::Number)
+                    .map_err(|_| self.error("number out of range"))
+            }
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.offset += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a member name"));
+            }
+            let name = self.parse_string()?;
+
+            self.skip_whitespace();
+            if self.next() != Some(':') {
+                return Err(self.error("expected ':'"));
+            }
+
+            members.push((name, self.parse_value()?));
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.offset += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(values)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.offset += 1;
+
+        let mut text = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+
+                    text.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(c) => text.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let unit = self.parse_hex_unit()?;
+
+        // Characters outside the basic multilingual plane come as a surrogate pair
+        let code = if (0xD800..0xDC00).contains(&unit) {
+            if !self.text[self.offset..].starts_with("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            self.offset += 2;
+
+            let low = self.parse_hex_unit()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate"));
+            }
+
+            0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            unit
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex_unit(&mut self) -> Result<u32, JsonError> {
+        let digits = self
+            .text
+            .get(self.offset..self.offset + 4)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        let unit =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid unicode escape"))?;
+
+        self.offset += 4;
+        Ok(unit)
+    }
+}
+
+impl Message {
+    /// The RFC 8427 form of the message. Its own encoding goes in `messageOctetsHEX`, as that
+    /// is the only place the reserved Z bit can be kept.
+    pub fn to_json(&self) -> Result<String, EncodeError> {
+        encode_message(self, Some(&self.encode(true)))
+    }
+
+    pub fn from_json(text: &str) -> Result<Message, JsonError> {
+        Ok(decode_message(text)?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WireError;
+    use crate::types::tests::{Generator, CASES};
+
+    #[test]
+    fn messages_round_trip() {
+        for seed in 0..CASES {
+            let mut message = Generator::new(seed).message();
+
+            assert_eq!(
+                Message::from_json(&message.to_json().unwrap()),
+                Ok(message.clone()),
+                "seed {}",
+                seed
+            );
+
+            // Without the octets the reserved bit is lost, as RFC 8427 has no member for it
+            message.header.zero = false;
+
+            assert_eq!(
+                decode_message(&encode_message(&message, None).unwrap()),
+                Ok((message, None)),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn octets_are_returned_unchanged() {
+        for seed in 0..CASES {
+            let message = Generator::new(seed).message();
+
+            for compression in [false, true] {
+                let octets = message.encode(compression);
+                let text = encode_message(&message, Some(&octets)).unwrap();
+
+                assert_eq!(
+                    decode_message(&text),
+                    Ok((message.clone(), Some(octets))),
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+
+        // A name written out in full where it could have been compressed decodes to the same
+        // message as the canonical form, so only the returned octets still have it
+        let message = Message::builder()
+            .questions(vec![Question {
+                name: "example.com.".parse().unwrap(),
+                question_type: ResourceType::A,
+                question_class: ResourceClass::IN,
+            }])
+            .answers(vec![Answer {
+                name: "example.com.".parse().unwrap(),
+                resource_type: ResourceType::A,
+                class: ResourceClass::IN,
+                time_to_live: 300,
+                data: RData::A("192.0.2.1".parse().unwrap()),
+            }])
+            .build();
+
+        let octets = message.encode(false);
+        let (decoded, returned) =
+            decode_message(&format!(r#"{{"messageOctetsHEX": "{}"}}"#, hex(&octets))).unwrap();
+
+        assert_eq!(decoded, message);
+        assert_ne!(decoded.encode(true), octets);
+        assert_eq!(returned, Some(octets));
+    }
+
+    #[test]
+    fn shortcuts_and_presentation_rdata() {
+        let message = decode_message(
+            r#"{
+                "ID": 1, "RD": 1, "QNAME": "example.com.", "QTYPE": 1, "QCLASS": 1,
+                "answerRRs": [
+                    {"NAME": "example.com.", "TYPE": 1, "CLASS": 1, "TTL": 300, "rdataA": "192.0.2.1"}
+                ]
+            }"#,
+        )
+        .unwrap()
+        .0;
+
+        assert_eq!(message.header.id, 1);
+        assert!(message.header.recursion_desired);
+        assert_eq!(message.questions[0].name, "example.com.".parse().unwrap());
+        assert_eq!(
+            message.answers[0].data,
+            RData::A("192.0.2.1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn malformed_json_is_rejected() {
+        let syntax = |text: &str| {
+            assert!(
+                matches!(decode_message(text), Err(JsonError::Syntax { .. })),
+                "{}",
+                text
+            )
+        };
+
+        syntax("");
+        syntax("{");
+        syntax(r#"{"ID": 1,}"#);
+        syntax(r#"{"ID" 1}"#);
+        syntax("[1 2]");
+        syntax("{} {}");
+        syntax("tru");
+        syntax("-1");
+        syntax("18446744073709551616");
+        syntax(r#""unterminated"#);
+        syntax(r#""\q""#);
+        syntax(r#""\ud800""#);
+        syntax(r#""\ud800A""#);
+        syntax("\"\u{1}\"");
+        syntax(&"[".repeat(200_000));
+        syntax(&r#"{"a":"#.repeat(200_000));
+
+        assert_eq!(
+            decode_message(r#"{"ID": x}"#),
+            Err(JsonError::Syntax {
+                offset: 7,
+                message: "unexpected character".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_members_are_rejected() {
+        for (text, error) in [
+            (r#"{"ID": "1"}"#, JsonError::InvalidMember("ID".to_string())),
+            (
+                r#"{"ID": 65536}"#,
+                JsonError::InvalidMember("ID".to_string()),
+            ),
+            (r#"{"QR": 2}"#, JsonError::InvalidMember("QR".to_string())),
+            (
+                r#"{"QNAME": "example.com."}"#,
+                JsonError::MissingMember("QTYPE".to_string()),
+            ),
+            (
+                r#"{"answerRRs": [{"NAME": "a.", "TYPE": 1, "CLASS": 1, "TTL": 1}]}"#,
+                JsonError::MissingMember("RDATAHEX".to_string()),
+            ),
+            (
+                r#"{"additionalRRs": [
+                    {"NAME": ".", "TYPE": 41, "CLASS": 512, "TTL": 0, "RDATAHEX": ""},
+                    {"NAME": ".", "TYPE": 41, "CLASS": 512, "TTL": 0, "RDATAHEX": ""}
+                ]}"#,
+                JsonError::InvalidMember("additionalRRs".to_string()),
+            ),
+            (r#"{"messageOctetsHEX": "abc"}"#, JsonError::InvalidHex),
+            (r#"{"messageOctetsHEX": "+a"}"#, JsonError::InvalidHex),
+            (r#"{"messageOctetsHEX": "éa"}"#, JsonError::InvalidHex),
+            (
+                r#"{"messageOctetsHEX": "0000"}"#,
+                JsonError::Wire(WireError::TruncatedHeader { length: 2 }),
+            ),
+        ] {
+            assert_eq!(decode_message(text), Err(error), "{}", text);
+        }
+    }
+}
@@ -1,162 +1,140 @@
-use std::{env::args, net::UdpSocket};
-
-use crate::types::{
-    Answer, DomainName, Header, Message, OperationCode, Question, QuestionClass, QuestionType,
-    ResourceClass, ResourceType, ResponseCode,
+use std::{
+    env::args,
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
 };
 
+use bytes::{BufMut, BytesMut};
+
+use crate::cache::Cache;
+use crate::edns::MAX_UDP_PAYLOAD;
+use crate::server::{Server, Transport};
+use crate::upstream::Upstream;
+use crate::zone::ZoneStore;
+
+mod cache;
+mod edns;
+mod error;
+mod idna;
+mod idna_tables;
+#[cfg(feature = "json")]
+mod json;
+mod master;
+mod message_ref;
+mod parse;
+mod rdata;
+mod server;
 mod types;
+mod upstream;
+mod zone;
+
+const CACHE_CAPACITY: usize = 4096;
+const WORKER_COUNT: usize = 16;
+const TCP_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_TCP_CONNECTIONS: usize = 128;
+const TCP_QUEUE_LENGTH: usize = 64;
+
+/// A query read from a TCP connection, waiting for a worker to answer it.
+struct TcpQuery {
+    query: Vec<u8>,
+    source: SocketAddr,
+    writer: Arc<Mutex<TcpStream>>,
+}
 
 fn main() {
     let receiver_socket =
         UdpSocket::bind("127.0.0.1:2053").expect("Failed to bind receiver socket");
+    let tcp_listener = TcpListener::bind("127.0.0.1:2053").expect("Failed to bind TCP listener");
 
-    let resolver_socket =
-        if let Some(addr) = args().skip_while(|arg| arg != "--resolver").skip(1).next() {
-            println!("Attempting to bind to resolver... {:?}", addr);
+    let upstream = if let Some(addr) = args().skip_while(|arg| arg != "--resolver").nth(1) {
+        println!("Attempting to bind to resolver... {:?}", addr);
 
-            let resolver_socket =
-                UdpSocket::bind("127.0.0.1:2054").expect("Failed to bind resolver socket");
+        Some(Upstream::connect(addr).expect("Unable to connect to the resolver server"))
+    } else {
+        None
+    };
 
-            resolver_socket
-                .connect(addr)
-                .expect("Unable to connect to the resolver server");
+    let mut zones = ZoneStore::new();
 
-            Some(resolver_socket)
-        } else {
-            None
-        };
+    let arguments: Vec<String> = args().collect();
 
-    let mut buf = [0; 512];
+    for path in arguments
+        .windows(2)
+        .filter(|pair| pair[0] == "--zone")
+        .map(|pair| &pair[1])
+    {
+        println!("Loading zone file {}", path);
 
-    loop {
-        match receiver_socket.recv_from(&mut buf) {
-            Ok((size, source)) => {
-                println!("Received {} bytes from {}", size, source);
+        if let Err(e) = zones.load(path) {
+            panic!("Unable to load zone file: {}", e);
+        }
+    }
 
-                let received_message = Message::try_from(buf.to_vec());
-
-                println!("1. Received Message: {:?}", received_message);
-
-                let response_message = if let Ok(received_message) = received_message {
-                    let mut answers = Vec::new();
-
-                    for question in &received_message.questions {
-                        match &resolver_socket {
-                            Some(resolver_socket) => {
-                                println!("Resolving using resolver socket");
-
-                                let resolver_message = Message {
-                                    header: Header {
-                                        id: rand::random(),
-                                        qr_indicator: received_message.header.qr_indicator,
-                                        op_code: received_message.header.op_code,
-                                        authoritative_answer: false,
-                                        truncation: false,
-                                        recursion_desired: false,
-                                        recursion_available: false,
-                                        response_code: ResponseCode::NoError,
-                                        question_count: 1,
-                                        answer_count: 0,
-                                        authority_count: 0,
-                                        additional_count: 0,
-                                    },
-                                    questions: vec![question.clone()],
-                                    answers: vec![],
-                                };
-
-                                let resolver_message: Vec<u8> = resolver_message.into();
-
-                                resolver_socket
-                                    .send(&resolver_message)
-                                    .expect("Unknown error when trying to forward request");
-
-                                resolver_socket
-                                    .recv_from(&mut buf)
-                                    .expect("No response from resolver socket");
-
-                                let resolver_response = Message::try_from(buf.to_vec())
-                                    .expect("Unable to parse resolver response");
-
-                                println!("Resolver Response: {:?}", resolver_response);
-
-                                answers.extend(resolver_response.answers);
-                            }
-                            None => {
-                                answers.push(Answer {
-                                    name: question.name.clone(),
-                                    resource_type: ResourceType::A,
-                                    class: ResourceClass::IN,
-                                    time_to_live: 60,
-                                    length: 4,
-                                    data: vec![8, 8, 8, 8],
-                                });
-                            }
-                        }
-                    }
+    // Name compression can make responses harder to read in packet captures, so allow turning it off
+    let compression = !args().any(|arg| arg == "--no-compression");
+
+    let server = Arc::new(Server {
+        zones,
+        cache: Mutex::new(Cache::new(CACHE_CAPACITY)),
+        upstream,
+        compression,
+    });
+
+    // Each worker blocks on its own query, so a slow upstream only holds up the worker waiting on
+    // it while the others keep serving
+    let mut workers: Vec<_> = (0..WORKER_COUNT)
+        .map(|_| {
+            let socket = receiver_socket
+                .try_clone()
+                .expect("Failed to clone receiver socket");
+            let server = Arc::clone(&server);
+
+            thread::spawn(move || serve_udp(&socket, &server))
+        })
+        .collect();
+
+    // TCP queries from every connection share their own pool of workers, so pipelining can't
+    // start more threads than that. Once the queue is full, connections stop reading until a
+    // worker catches up.
+    let (tcp_queries, receiver) = mpsc::sync_channel(TCP_QUEUE_LENGTH);
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    workers.extend((0..WORKER_COUNT).map(|_| {
+        let receiver = Arc::clone(&receiver);
+        let server = Arc::clone(&server);
+
+        thread::spawn(move || answer_tcp(&receiver, &server))
+    }));
+
+    workers.push(thread::spawn(move || serve_tcp(&tcp_listener, tcp_queries)));
+
+    for worker in workers {
+        worker.join().expect("Worker thread panicked");
+    }
+}
 
-                    let answers = answers;
-
-                    Message {
-                        header: Header {
-                            id: received_message.header.id,
-                            qr_indicator: true,
-                            op_code: received_message.header.op_code,
-                            authoritative_answer: false,
-                            truncation: false,
-                            recursion_desired: received_message.header.recursion_desired,
-                            recursion_available: false,
-                            response_code: match received_message.header.op_code {
-                                OperationCode::Query => ResponseCode::NoError,
-                                _ => ResponseCode::NotImplemented,
-                            },
-                            question_count: received_message.questions.len() as u16,
-                            answer_count: answers.len() as u16,
-                            authority_count: 0,
-                            additional_count: 0,
-                        },
-                        questions: received_message.questions,
-                        answers,
-                    }
-                } else {
-                    Message {
-                        header: Header {
-                            id: 1234,
-                            qr_indicator: true,
-                            op_code: OperationCode::Query,
-                            authoritative_answer: false,
-                            truncation: false,
-                            recursion_desired: false,
-                            recursion_available: false,
-                            response_code: ResponseCode::FormatError,
-                            question_count: 1,
-                            answer_count: 1,
-                            authority_count: 0,
-                            additional_count: 0,
-                        },
-                        questions: vec![Question {
-                            name: DomainName::new("codecrafters.io".to_string()),
-                            question_type: QuestionType::A,
-                            question_class: QuestionClass::IN,
-                        }],
-                        answers: vec![Answer {
-                            name: DomainName::new("codecrafters.io".to_string()),
-                            resource_type: ResourceType::A,
-                            class: ResourceClass::IN,
-                            time_to_live: 60,
-                            length: 4,
-                            data: vec![8, 8, 8, 8],
-                        }],
-                    }
-                };
+fn serve_udp(socket: &UdpSocket, server: &Server) {
+    let mut buf = [0; MAX_UDP_PAYLOAD as usize];
+    let mut response = BytesMut::with_capacity(MAX_UDP_PAYLOAD as usize);
 
-                println!("2. Response Message: {:?}", response_message);
+    loop {
+        match socket.recv_from(&mut buf) {
+            Ok((size, source)) => {
+                println!("Received {} bytes from {}", size, source);
 
-                let response: Vec<u8> = response_message.into();
+                response.clear();
+                server.handle(&buf[..size], Transport::Udp, &mut response);
 
-                receiver_socket
-                    .send_to(&response, source)
-                    .expect("Failed to send response");
+                if let Err(e) = socket.send_to(&response, source) {
+                    eprintln!("Failed to send response to {}: {}", source, e);
+                }
             }
             Err(e) => {
                 eprintln!("Error receiving data: {}", e);
@@ -165,3 +143,111 @@ fn main() {
         }
     }
 }
+
+fn serve_tcp(listener: &TcpListener, queries: SyncSender<TcpQuery>) {
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // Each connection has a thread reading from it, so only so many can be open
+                if connections.load(Ordering::SeqCst) >= MAX_TCP_CONNECTIONS {
+                    eprintln!("Too many TCP connections, closing the newest");
+                    continue;
+                }
+
+                connections.fetch_add(1, Ordering::SeqCst);
+
+                let connections = Arc::clone(&connections);
+                let queries = queries.clone();
+
+                thread::spawn(move || {
+                    if let Err(e) = serve_tcp_connection(stream, &queries) {
+                        eprintln!("TCP connection closed: {}", e);
+                    }
+
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Err(e) => eprintln!("Error accepting TCP connection: {}", e),
+        }
+    }
+}
+
+/// Reads length-prefixed queries until the client goes quiet (RFC 7766). Queries are answered as
+/// soon as they resolve, so pipelined responses may come back out of order and clients match them
+/// by ID.
+fn serve_tcp_connection(
+    mut stream: TcpStream,
+    queries: &SyncSender<TcpQuery>,
+) -> std::io::Result<()> {
+    let source = stream.peer_addr()?;
+    stream.set_read_timeout(Some(TCP_IDLE_TIMEOUT))?;
+    // A client that stops reading responses would otherwise hold up a worker forever
+    stream.set_write_timeout(Some(TCP_IDLE_TIMEOUT))?;
+
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+
+    loop {
+        let mut length = [0; 2];
+
+        match stream.read_exact(&mut length) {
+            Ok(()) => {}
+            // The client closing the connection or going idle between queries is a normal end
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::UnexpectedEof | ErrorKind::WouldBlock | ErrorKind::TimedOut
+                ) =>
+            {
+                return Ok(())
+            }
+            Err(e) => return Err(e),
+        }
+
+        let mut query = vec![0; u16::from_be_bytes(length) as usize];
+        stream.read_exact(&mut query)?;
+
+        println!("Received {} bytes over TCP from {}", query.len(), source);
+
+        let query = TcpQuery {
+            query,
+            source,
+            writer: Arc::clone(&writer),
+        };
+
+        // The workers only stop when the server does
+        if queries.send(query).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+fn answer_tcp(queries: &Mutex<Receiver<TcpQuery>>, server: &Server) {
+    loop {
+        // Only hold the lock while waiting, so other workers can take the next query
+        let Ok(TcpQuery {
+            query,
+            source,
+            writer,
+        }) = queries.lock().unwrap().recv()
+        else {
+            return;
+        };
+
+        // Leave room for the length prefix and fill it in once the response is written
+        let mut framed = BytesMut::with_capacity(512);
+        framed.put_u16(0);
+
+        server.handle(&query, Transport::Tcp, &mut framed);
+
+        let length = (framed.len() - 2) as u16;
+        framed[..2].copy_from_slice(&length.to_be_bytes());
+
+        let sent = writer.lock().unwrap().write_all(&framed);
+
+        if let Err(e) = sent {
+            eprintln!("Failed to send TCP response to {}: {}", source, e);
+        }
+    }
+}
@@ -0,0 +1,674 @@
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use crate::error::InvalidRecord;
+use crate::idna;
+use crate::rdata::RData;
+use crate::types::{Answer, DomainName, ResourceClass, ResourceType};
+
+#[derive(Debug)]
+pub(crate) struct Token {
+    pub(crate) text: String,
+    pub(crate) quoted: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub(crate) line: usize,
+    pub(crate) owner_blank: bool,
+    pub(crate) tokens: Vec<Token>,
+}
+
+/// Splits master file text into entries, joining lines inside parentheses and dropping comments.
+/// Escapes are left in the token text so each field can interpret them.
+pub(crate) fn tokenize(contents: &str) -> Result<Vec<Entry>, (usize, String)> {
+    let mut entries = Vec::new();
+    let mut tokens = Vec::new();
+    let mut token: Option<String> = None;
+
+    let mut line = 1;
+    let mut entry_line = 1;
+    let mut owner_blank = false;
+    let mut at_line_start = true;
+    let mut depth = 0;
+
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if at_line_start && depth == 0 {
+            entry_line = line;
+            owner_blank = c == ' ' || c == '\t';
+        }
+        at_line_start = false;
+
+        match c {
+            '\n' => {
+                if let Some(text) = token.take() {
+                    tokens.push(Token {
+                        text,
+                        quoted: false,
+                    });
+                }
+
+                if depth == 0 {
+                    if !tokens.is_empty() {
+                        entries.push(Entry {
+                            line: entry_line,
+                            owner_blank,
+                            tokens: std::mem::take(&mut tokens),
+                        });
+                    }
+
+                    at_line_start = true;
+                }
+
+                line += 1;
+            }
+            ';' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '"' => {
+                if let Some(text) = token.take() {
+                    tokens.push(Token {
+                        text,
+                        quoted: false,
+                    });
+                }
+
+                let mut text = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            text.push('\\');
+                            text.extend(chars.next());
+                        }
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => return Err((line, "unterminated quoted string".to_string())),
+                    }
+                }
+
+                tokens.push(Token { text, quoted: true });
+            }
+            '(' | ')' => {
+                if let Some(text) = token.take() {
+                    tokens.push(Token {
+                        text,
+                        quoted: false,
+                    });
+                }
+
+                if c == '(' {
+                    depth += 1;
+                } else if depth == 0 {
+                    return Err((line, "unbalanced closing parenthesis".to_string()));
+                } else {
+                    depth -= 1;
+                }
+            }
+            ' ' | '\t' | '\r' => {
+                if let Some(text) = token.take() {
+                    tokens.push(Token {
+                        text,
+                        quoted: false,
+                    });
+                }
+            }
+            '\\' => {
+                let text = token.get_or_insert_with(String::new);
+                text.push('\\');
+                text.extend(chars.next());
+            }
+            _ => token.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if depth != 0 {
+        return Err((line, "unbalanced opening parenthesis".to_string()));
+    }
+
+    if let Some(text) = token.take() {
+        tokens.push(Token {
+            text,
+            quoted: false,
+        });
+    }
+
+    if !tokens.is_empty() {
+        entries.push(Entry {
+            line: entry_line,
+            owner_blank,
+            tokens,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Reads resource records written in master file syntax (RFC 1035 §5.1), such as
+/// `www 300 IN A 192.0.2.1`. Relative names are completed with the origin, and a record may leave
+/// out its owner, TTL or class to reuse the ones from before.
+#[derive(Debug, Default)]
+pub struct RecordParser {
+    pub origin: Option<DomainName>,
+    pub default_ttl: Option<u32>,
+    last_ttl: Option<u32>,
+    last_owner: Option<DomainName>,
+}
+
+impl RecordParser {
+    pub fn new(origin: Option<DomainName>, default_ttl: Option<u32>) -> Self {
+        RecordParser {
+            origin,
+            default_ttl,
+            ..RecordParser::default()
+        }
+    }
+
+    /// Parses a single record, which may span several lines inside parentheses.
+    pub fn parse(&mut self, text: &str) -> Result<Answer, InvalidRecord> {
+        let entries = tokenize(text).map_err(|(_, message)| InvalidRecord(message))?;
+
+        let [entry] = entries.as_slice() else {
+            return Err(InvalidRecord("expected exactly one record".to_string()));
+        };
+
+        if !entry.owner_blank && entry.tokens[0].text.starts_with('$') {
+            return Err(InvalidRecord(
+                "directives are only allowed in zone files".to_string(),
+            ));
+        }
+
+        self.parse_entry(entry).map_err(InvalidRecord)
+    }
+
+    pub(crate) fn parse_entry(&mut self, entry: &Entry) -> Result<Answer, String> {
+        let mut tokens = entry.tokens.iter().peekable();
+
+        let name = if entry.owner_blank {
+            self.last_owner
+                .clone()
+                .ok_or("record has no owner name and there is no previous owner")?
+        } else {
+            let owner = tokens.next().ok_or("missing owner name")?;
+            parse_name(&owner.text, self.origin.as_ref())?
+        };
+
+        // The TTL and class are both optional and may appear in either order
+        let mut ttl = None;
+        let mut class = None;
+        let mut resource_type = None;
+
+        while let Some(token) = tokens.next_if(|token| !token.quoted) {
+            if ttl.is_none() {
+                if let Some(value) = parse_ttl(&token.text) {
+                    ttl = Some(value);
+                    continue;
+                }
+            }
+
+            if class.is_none() {
+                if let Ok(value) = ResourceClass::from_str(&token.text) {
+                    class = Some(value);
+                    continue;
+                }
+            }
+
+            resource_type = Some(token);
+            break;
+        }
+
+        let resource_type = resource_type.ok_or("missing record type")?;
+        let resource_type = ResourceType::from_str(&resource_type.text)
+            .map_err(|_| format!("unknown record type {}", resource_type.text))?;
+
+        let time_to_live = match ttl {
+            Some(ttl) => {
+                self.last_ttl = Some(ttl);
+                ttl
+            }
+            None => self
+                .default_ttl
+                .or(self.last_ttl)
+                .ok_or("record has no TTL and no $TTL is set")?,
+        };
+
+        let rdata: Vec<&Token> = tokens.collect();
+        let data = parse_rdata(resource_type, &rdata, self.origin.as_ref())?;
+
+        self.last_owner = Some(name.clone());
+
+        Ok(Answer {
+            name,
+            resource_type,
+            class: class.unwrap_or(ResourceClass::IN),
+            time_to_live,
+            data,
+        })
+    }
+}
+
+/// The TTL given to a record parsed on its own when it doesn't have one.
+pub const DEFAULT_TTL: u32 = 3600;
+
+/// Parses a record on its own, with names taken relative to the root, so `@ IN MX 10 mail` is
+/// `. 3600 IN MX 10 mail.`. A record without a TTL gets [`DEFAULT_TTL`].
+impl FromStr for Answer {
+    type Err = InvalidRecord;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        RecordParser::new(Some(DomainName::root()), Some(DEFAULT_TTL)).parse(text)
+    }
+}
+
+pub(crate) fn parse_name(text: &str, origin: Option<&DomainName>) -> Result<DomainName, String> {
+    if text == "@" {
+        return origin
+            .cloned()
+            .ok_or_else(|| "@ used before $ORIGIN is set".to_string());
+    }
+
+    // Unicode names can be written as is, rather than having to be converted to punycode by hand
+    let (name, absolute) = if text.is_ascii() {
+        (text.parse(), DomainName::is_absolute(text))
+    } else {
+        (
+            DomainName::from_unicode(text),
+            text.ends_with(idna::is_label_separator),
+        )
+    };
+
+    let name = name.map_err(|e| format!("invalid name {}: {}", text, e))?;
+
+    if absolute {
+        return Ok(name);
+    }
+
+    let origin =
+        origin.ok_or_else(|| format!("relative name {} used before $ORIGIN is set", text))?;
+
+    name.join(origin)
+        .map_err(|e| format!("invalid name {}: {}", text, e))
+}
+
+/// Parses a TTL given either in seconds or with BIND style units such as `1h30m`.
+pub(crate) fn parse_ttl(text: &str) -> Option<u32> {
+    if let Ok(seconds) = text.parse() {
+        return Some(seconds);
+    }
+
+    if !text.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut total: u32 = 0;
+    let mut value: u32 = 0;
+
+    for c in text.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            value = value.checked_mul(10)?.checked_add(digit)?;
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+
+        total = total.checked_add(value.checked_mul(unit)?)?;
+        value = 0;
+    }
+
+    // A trailing bare number would be ambiguous, so units must follow every number
+    (value == 0 && text.ends_with(|c: char| c.is_ascii_alphabetic())).then_some(total)
+}
+
+/// Resolves `\X` and `\DDD` escapes into the raw bytes they stand for.
+fn unescape(text: &str) -> Result<Vec<u8>, String> {
+    let mut res = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            res.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        match bytes.get(i + 1..i + 4) {
+            Some(digits) if digits.iter().all(u8::is_ascii_digit) => {
+                let value = digits
+                    .iter()
+                    .fold(0u16, |acc, digit| acc * 10 + (digit - b'0') as u16);
+
+                res.push(u8::try_from(value).map_err(|_| format!("invalid escape in {}", text))?);
+                i += 4;
+            }
+            _ => {
+                let escaped = bytes
+                    .get(i + 1)
+                    .ok_or_else(|| format!("dangling escape in {}", text))?;
+
+                res.push(*escaped);
+                i += 2;
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+fn parse_rdata(
+    resource_type: ResourceType,
+    tokens: &[&Token],
+    origin: Option<&DomainName>,
+) -> Result<RData, String> {
+    let text: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+
+    // RFC 3597 §5 generic encoding, usable for any type
+    if let Some((&"\\#", rest)) = text.split_first() {
+        let (length, hex) = rest.split_first().ok_or("missing generic RDATA length")?;
+        let length: u16 = length
+            .parse()
+            .map_err(|_| format!("invalid generic RDATA length {}", length))?;
+
+        let hex = hex.concat();
+        // Checked up front, as from_str_radix accepts a sign and slicing could split a character
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err("invalid hex in generic RDATA".to_string());
+        }
+
+        if hex.len() != length as usize * 2 {
+            return Err("generic RDATA length does not match its data".to_string());
+        }
+
+        let data = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "invalid hex in generic RDATA".to_string())?;
+
+        return RData::parse(&data, 0, length, resource_type).map_err(|e| e.to_string());
+    }
+
+    let number = |value: &str| {
+        value
+            .parse::<u16>()
+            .map_err(|_| format!("invalid number {}", value))
+    };
+    let ttl = |value: &str| parse_ttl(value).ok_or_else(|| format!("invalid time value {}", value));
+    let name = |value: &str| parse_name(value, origin);
+
+    let data = match (resource_type, text.as_slice()) {
+        (ResourceType::A, [address]) => RData::A(
+            Ipv4Addr::from_str(address).map_err(|_| format!("invalid IPv4 address {}", address))?,
+        ),
+        (ResourceType::AAAA, [address]) => RData::AAAA(
+            Ipv6Addr::from_str(address).map_err(|_| format!("invalid IPv6 address {}", address))?,
+        ),
+        (ResourceType::CNAME, [target]) => RData::CNAME(name(target)?),
+        (ResourceType::NS, [target]) => RData::NS(name(target)?),
+        (ResourceType::PTR, [target]) => RData::PTR(name(target)?),
+        (ResourceType::MX, [preference, exchange]) => RData::MX {
+            preference: number(preference)?,
+            exchange: name(exchange)?,
+        },
+        (ResourceType::TXT, strings) if !strings.is_empty() => RData::TXT(
+            strings
+                .iter()
+                .map(|string| {
+                    let string = unescape(string)?;
+
+                    if string.len() > 255 {
+                        return Err("TXT strings can be at most 255 bytes".to_string());
+                    }
+
+                    Ok(string)
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+        (ResourceType::SOA, [mname, rname, serial, refresh, retry, expire, minimum]) => {
+            RData::SOA {
+                mname: name(mname)?,
+                rname: name(rname)?,
+                serial: serial
+                    .parse()
+                    .map_err(|_| format!("invalid serial {}", serial))?,
+                refresh: ttl(refresh)?,
+                retry: ttl(retry)?,
+                expire: ttl(expire)?,
+                minimum: ttl(minimum)?,
+            }
+        }
+        (ResourceType::SRV, [priority, weight, port, target]) => RData::SRV {
+            priority: number(priority)?,
+            weight: number(weight)?,
+            port: number(port)?,
+            target: name(target)?,
+        },
+        (ResourceType::CAA, [flags, tag, value]) => {
+            // RFC 8659 §4.1 limits tags to 15 letters and digits
+            if tag.is_empty() || tag.len() > 15 || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(format!("invalid CAA tag {}", tag));
+            }
+
+            RData::CAA {
+                flags: flags
+                    .parse()
+                    .map_err(|_| format!("invalid CAA flags {}", flags))?,
+                tag: tag.to_string(),
+                value: unescape(value)?,
+            }
+        }
+        (
+            ResourceType::A
+            | ResourceType::AAAA
+            | ResourceType::CNAME
+            | ResourceType::NS
+            | ResourceType::PTR
+            | ResourceType::MX
+            | ResourceType::TXT
+            | ResourceType::SOA
+            | ResourceType::SRV
+            | ResourceType::CAA,
+            _,
+        ) => {
+            return Err(format!(
+                "wrong number of fields for {:?} record",
+                resource_type
+            ))
+        }
+        _ => {
+            return Err(format!(
+                "{:?} records must use the generic \\# syntax",
+                resource_type
+            ))
+        }
+    };
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser() -> RecordParser {
+        RecordParser::new(Some("example.".parse().unwrap()), None)
+    }
+
+    #[test]
+    fn ttl_and_class_come_in_either_order() {
+        let expected = Answer {
+            name: "www.example.".parse().unwrap(),
+            resource_type: ResourceType::A,
+            class: ResourceClass::IN,
+            time_to_live: 300,
+            data: RData::A("192.0.2.1".parse().unwrap()),
+        };
+
+        for text in [
+            "www 300 IN A 192.0.2.1",
+            "www IN 300 A 192.0.2.1",
+            "www.example. 5m A 192.0.2.1",
+        ] {
+            assert_eq!(parser().parse(text), Ok(expected.clone()), "{}", text);
+        }
+
+        // Without a TTL or $TTL the previous record's TTL is used
+        let mut records = parser();
+        records.parse("www 300 IN A 192.0.2.1").unwrap();
+        assert_eq!(records.parse("www IN A 192.0.2.1"), Ok(expected));
+        assert!(parser().parse("www IN A 192.0.2.1").is_err());
+    }
+
+    #[test]
+    fn names_are_completed_with_the_origin() {
+        assert_eq!(
+            parser().parse("@ 60 IN MX 10 mail"),
+            Ok(Answer {
+                name: "example.".parse().unwrap(),
+                resource_type: ResourceType::MX,
+                class: ResourceClass::IN,
+                time_to_live: 60,
+                data: RData::MX {
+                    preference: 10,
+                    exchange: "mail.example.".parse().unwrap(),
+                },
+            })
+        );
+
+        // On its own a record is relative to the root and gets the default TTL
+        assert_eq!(
+            "@ IN MX 10 mail".parse(),
+            Ok(Answer {
+                name: DomainName::root(),
+                resource_type: ResourceType::MX,
+                class: ResourceClass::IN,
+                time_to_live: DEFAULT_TTL,
+                data: RData::MX {
+                    preference: 10,
+                    exchange: "mail.".parse().unwrap(),
+                },
+            })
+        );
+
+        let answer = parser()
+            .parse("alias 60 IN CNAME www.example.net.")
+            .unwrap();
+        assert_eq!(
+            answer.data,
+            RData::CNAME("www.example.net.".parse().unwrap())
+        );
+
+        assert!(RecordParser::default()
+            .parse("@ 60 IN A 192.0.2.1")
+            .is_err());
+    }
+
+    #[test]
+    fn txt_strings_are_quoted_and_escaped() {
+        let answer = parser()
+            .parse(r#"txt 60 IN TXT "hello world" "say \"hi\"" \065\066 plain"#)
+            .unwrap();
+
+        assert_eq!(
+            answer.data,
+            RData::TXT(vec![
+                b"hello world".to_vec(),
+                b"say \"hi\"".to_vec(),
+                b"AB".to_vec(),
+                b"plain".to_vec(),
+            ])
+        );
+
+        assert!(parser().parse(r#"txt 60 IN TXT "unterminated"#).is_err());
+    }
+
+    #[test]
+    fn records_can_span_lines_in_parentheses() {
+        let answer = parser()
+            .parse(
+                "@ 3600 IN SOA ns admin (\n\
+                     2024010101 ; serial\n\
+                     7200       ; refresh\n\
+                     3600 1209600\n\
+                     300 )",
+            )
+            .unwrap();
+
+        assert_eq!(
+            answer.data,
+            RData::SOA {
+                mname: "ns.example.".parse().unwrap(),
+                rname: "admin.example.".parse().unwrap(),
+                serial: 2024010101,
+                refresh: 7200,
+                retry: 3600,
+                expire: 1209600,
+                minimum: 300,
+            }
+        );
+
+        assert!(parser()
+            .parse("@ 3600 IN SOA ns admin ( 1 2 3 4 5")
+            .is_err());
+    }
+
+    #[test]
+    fn generic_rdata() {
+        let answer: Answer = "x. 300 IN TYPE99 \\# 3 0a0B ff".parse().unwrap();
+
+        assert_eq!(
+            answer.data,
+            RData::Unknown(ResourceType::from(99), vec![0x0a, 0x0b, 0xff])
+        );
+
+        for text in [
+            "x. 300 IN TYPE99 \\# 2 aé1",
+            "x. 300 IN TYPE99 \\# 1 é",
+            "x. 300 IN TYPE99 \\# 2 0a0",
+            "x. 300 IN TYPE99 \\# 1 zz",
+            "x. 300 IN TYPE99 \\# 1 +a",
+            "x. 300 IN TYPE99 \\#",
+        ] {
+            assert!(text.parse::<Answer>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn caa_tags() {
+        let answer: Answer = "x. 300 IN CAA 0 issue \"ca.example.net\"".parse().unwrap();
+
+        assert_eq!(
+            answer.data,
+            RData::CAA {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: b"ca.example.net".to_vec(),
+            }
+        );
+
+        let long = format!("x. 300 IN CAA 0 {} \"\"", "a".repeat(300));
+
+        for text in [
+            long.as_str(),
+            "x. 300 IN CAA 0 issue-wild \"\"",
+            "x. 300 IN CAA 0 \"\" \"\"",
+            "x. 300 IN CAA 0 tàg \"\"",
+        ] {
+            assert!(text.parse::<Answer>().is_err(), "{}", text);
+        }
+    }
+}
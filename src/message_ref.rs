@@ -0,0 +1,464 @@
+use crate::edns::{extended_response_code, Edns};
+use crate::error::WireError;
+use crate::parse::{self, HEADER_LENGTH};
+use crate::rdata::RData;
+use crate::types::{
+    Answer, DomainName, Header, Label, Message, Question, ResourceClass, ResourceType, ResponseCode,
+};
+
+/// A borrowed view over an encoded message. Only the header is decoded up front; questions and
+/// records are decoded one at a time as they are iterated, without copying out of the buffer.
+#[derive(Debug, Clone)]
+pub struct MessageRef<'a> {
+    buf: &'a [u8],
+    header: Header,
+}
+
+/// How closely a message is checked while decoding it. The default rejects anything that isn't
+/// well formed, but doesn't hold the message to the rules for queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+    /// Keep whatever can be decoded from a malformed message and report what was wrong with it
+    /// as warnings, instead of failing.
+    pub lenient: bool,
+    /// Also check that a query has exactly one question (RFC 9619) and that the reserved Z bit is
+    /// clear.
+    pub validate: bool,
+}
+
+impl ParseOptions {
+    /// For queries from clients, which are rejected if anything is wrong with them.
+    pub fn strict() -> Self {
+        ParseOptions {
+            lenient: false,
+            validate: true,
+        }
+    }
+
+    /// For responses from upstream, where a partial answer is better than none.
+    pub fn lenient() -> Self {
+        ParseOptions {
+            lenient: true,
+            validate: true,
+        }
+    }
+}
+
+/// A decoded message, along with the problems that were worked around to decode it.
+#[derive(Debug, Clone)]
+pub struct ParsedMessage {
+    pub message: Message,
+    pub warnings: Vec<WireError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Answer,
+    Authority,
+    Additional,
+}
+
+impl<'a> MessageRef<'a> {
+    pub fn parse(buf: &'a [u8]) -> Result<Self, WireError> {
+        let (_, header) =
+            parse::header(buf).map_err(|_| WireError::TruncatedHeader { length: buf.len() })?;
+
+        Ok(MessageRef { buf, header })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn questions(&self) -> Questions<'a> {
+        Questions {
+            buf: self.buf,
+            offset: HEADER_LENGTH,
+            remaining: self.header.question_count,
+        }
+    }
+
+    /// Iterates the answer, authority and additional sections in order, tagging each record with
+    /// the section it came from.
+    pub fn records(&self) -> Result<Records<'a>, WireError> {
+        let mut questions = self.questions();
+
+        for question in questions.by_ref() {
+            question?;
+        }
+
+        Ok(Records {
+            buf: self.buf,
+            offset: questions.offset,
+            remaining: [
+                self.header.answer_count,
+                self.header.authority_count,
+                self.header.additional_count,
+            ],
+        })
+    }
+
+    pub fn answers(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<RecordRef<'a>, WireError>>, WireError> {
+        self.section(Section::Answer)
+    }
+
+    pub fn authorities(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<RecordRef<'a>, WireError>>, WireError> {
+        self.section(Section::Authority)
+    }
+
+    pub fn additionals(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<RecordRef<'a>, WireError>>, WireError> {
+        self.section(Section::Additional)
+    }
+
+    fn section(
+        &self,
+        section: Section,
+    ) -> Result<impl Iterator<Item = Result<RecordRef<'a>, WireError>>, WireError> {
+        Ok(self.records()?.filter_map(move |record| match record {
+            Ok((record_section, record)) => (record_section == section).then_some(Ok(record)),
+            Err(e) => Some(Err(e)),
+        }))
+    }
+
+    /// Decodes the whole message into an owned [`Message`], rejecting any bytes left over at
+    /// the end.
+    pub fn to_message(&self) -> Result<Message, WireError> {
+        self.to_message_with(ParseOptions::default())
+            .map(|parsed| parsed.message)
+    }
+
+    /// Decodes the whole message into an owned [`Message`]. When `options` is lenient, problems
+    /// are collected as warnings: records with bad RDATA are dropped, and decoding stops at the
+    /// first record that can't be found, keeping everything before it.
+    pub fn to_message_with(&self, options: ParseOptions) -> Result<ParsedMessage, WireError> {
+        let mut warnings = Vec::new();
+        let mut problem = |error: WireError| {
+            if options.lenient {
+                warnings.push(error);
+                Ok(())
+            } else {
+                Err(error)
+            }
+        };
+
+        if options.validate {
+            if self.header.zero {
+                problem(WireError::ReservedBitSet)?;
+            }
+
+            if !self.header.qr_indicator && self.header.question_count != 1 {
+                problem(WireError::QuestionCount {
+                    count: self.header.question_count,
+                })?;
+            }
+        }
+
+        let mut questions = Vec::with_capacity(self.header.question_count as usize);
+        let mut answers = Vec::with_capacity(self.header.answer_count as usize);
+        let mut authorities = Vec::with_capacity(self.header.authority_count as usize);
+        let mut additionals = Vec::with_capacity(self.header.additional_count as usize);
+        let mut edns = None;
+        let mut header = self.header.clone();
+
+        for question in self.questions() {
+            match question.and_then(QuestionRef::to_question) {
+                Ok(question) => questions.push(question),
+                Err(e) => {
+                    problem(e)?;
+                    break;
+                }
+            }
+        }
+
+        // Where the last record ends, if every record could be found
+        let mut end = None;
+
+        // The records can only be found once every question has been read
+        if questions.len() == self.header.question_count as usize {
+            let mut records = self.records()?;
+
+            end = loop {
+                let Some(record) = records.next() else {
+                    break Some(records.offset);
+                };
+
+                let (section, record) = match record {
+                    Ok(record) => record,
+                    Err(e) => {
+                        problem(e)?;
+                        break None;
+                    }
+                };
+
+                let answer = match record.to_answer() {
+                    Ok(answer) => answer,
+                    Err(e) => {
+                        problem(e)?;
+                        continue;
+                    }
+                };
+
+                match section {
+                    Section::Answer => answers.push(answer),
+                    Section::Authority => authorities.push(answer),
+                    // The OPT pseudo-record isn't real data, so it is pulled out of the
+                    // additional section
+                    Section::Additional if answer.resource_type == ResourceType::OPT => {
+                        if edns.is_some() {
+                            problem(WireError::DuplicateOpt {
+                                offset: record.offset,
+                            })?;
+                            continue;
+                        }
+
+                        match Edns::try_from(&answer) {
+                            Ok(opt) => edns = Some(opt),
+                            Err(e) => {
+                                problem(e)?;
+                                continue;
+                            }
+                        }
+
+                        header.response_code = ResponseCode::from_parts(
+                            header.response_code.low_bits(),
+                            extended_response_code(&answer),
+                        );
+                    }
+                    Section::Additional => additionals.push(answer),
+                }
+            };
+        }
+
+        if let Some(end) = end.filter(|&end| end != self.buf.len()) {
+            problem(WireError::TrailingBytes {
+                offset: end,
+                remaining: self.buf.len() - end,
+            })?;
+        }
+
+        // Anything dropped along the way is no longer counted
+        header.question_count = questions.len() as u16;
+        header.answer_count = answers.len() as u16;
+        header.authority_count = authorities.len() as u16;
+        header.additional_count = additionals.len() as u16 + edns.is_some() as u16;
+
+        Ok(ParsedMessage {
+            message: Message {
+                header,
+                questions,
+                answers,
+                authorities,
+                additionals,
+                edns,
+            },
+            warnings,
+        })
+    }
+}
+
+impl TryFrom<MessageRef<'_>> for Message {
+    type Error = WireError;
+
+    fn try_from(value: MessageRef<'_>) -> Result<Self, Self::Error> {
+        value.to_message()
+    }
+}
+
+/// A possibly compressed name inside a message buffer. The name is validated when it is found,
+/// so walking its labels afterwards cannot fail.
+#[derive(Debug, Clone, Copy)]
+pub struct NameRef<'a> {
+    pub(crate) buf: &'a [u8],
+    pub(crate) offset: usize,
+    pub(crate) wire_length: usize,
+}
+
+impl<'a> NameRef<'a> {
+    pub fn parse(buf: &'a [u8], start: usize) -> Result<Self, WireError> {
+        let input = buf
+            .get(start..)
+            .ok_or(WireError::UnexpectedEnd { offset: start })?;
+
+        parse::run(buf, input, parse::name(buf)).map(|(_, name)| name)
+    }
+
+    /// The number of bytes the name takes up where it appears, up to and including the first
+    /// pointer.
+    pub fn wire_length(&self) -> usize {
+        self.wire_length
+    }
+
+    pub fn labels(&self) -> Labels<'a> {
+        Labels {
+            buf: self.buf,
+            offset: self.offset,
+        }
+    }
+
+    pub fn to_domain_name(self) -> Result<DomainName, WireError> {
+        // The lengths were checked when the name was parsed, so this can only fail if those
+        // checks and the owned name's disagree
+        self.labels()
+            .map(Label::new)
+            .collect::<Result<_, _>>()
+            .and_then(DomainName::from_labels)
+            .map_err(|_| WireError::NameTooLong {
+                offset: self.offset,
+            })
+    }
+}
+
+pub struct Labels<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Labels<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let first_byte = self.buf[self.offset];
+
+            if first_byte & 0b1100_0000 == 0b1100_0000 {
+                self.offset = (u16::from_be_bytes([first_byte, self.buf[self.offset + 1]])
+                    & !0b1100_0000_0000_0000) as usize;
+                continue;
+            }
+
+            if first_byte == 0 {
+                return None;
+            }
+
+            let start = self.offset + 1;
+            self.offset = start + first_byte as usize;
+
+            return Some(&self.buf[start..self.offset]);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct QuestionRef<'a> {
+    pub name: NameRef<'a>,
+    pub question_type: ResourceType,
+    pub question_class: ResourceClass,
+}
+
+impl QuestionRef<'_> {
+    pub fn to_question(self) -> Result<Question, WireError> {
+        Ok(Question {
+            name: self.name.to_domain_name()?,
+            question_type: self.question_type,
+            question_class: self.question_class,
+        })
+    }
+}
+
+pub struct Questions<'a> {
+    buf: &'a [u8],
+    offset: usize,
+    remaining: u16,
+}
+
+impl<'a> Iterator for Questions<'a> {
+    type Item = Result<QuestionRef<'a>, WireError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let question = parse::run(
+            self.buf,
+            &self.buf[self.offset..],
+            parse::question(self.buf),
+        )
+        .map(|(offset, question)| {
+            self.offset = offset;
+            question
+        });
+
+        // Nothing after a malformed question can be found, so stop there
+        if question.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(question)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RecordRef<'a> {
+    pub(crate) buf: &'a [u8],
+    pub(crate) offset: usize,
+    pub name: NameRef<'a>,
+    pub resource_type: ResourceType,
+    pub class: ResourceClass,
+    pub time_to_live: u32,
+    pub(crate) rdata_offset: usize,
+    pub(crate) rdata_length: u16,
+}
+
+impl<'a> RecordRef<'a> {
+    /// The raw RDATA. Names inside it may be compressed against the rest of the message.
+    pub fn raw_data(&self) -> &'a [u8] {
+        &self.buf[self.rdata_offset..self.rdata_offset + self.rdata_length as usize]
+    }
+
+    pub fn data(&self) -> Result<RData, WireError> {
+        RData::parse(
+            self.buf,
+            self.rdata_offset,
+            self.rdata_length,
+            self.resource_type,
+        )
+    }
+
+    pub fn to_answer(self) -> Result<Answer, WireError> {
+        Ok(Answer {
+            name: self.name.to_domain_name()?,
+            resource_type: self.resource_type,
+            class: self.class,
+            time_to_live: self.time_to_live,
+            data: self.data()?,
+        })
+    }
+}
+
+pub struct Records<'a> {
+    buf: &'a [u8],
+    offset: usize,
+    remaining: [u16; 3],
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<(Section, RecordRef<'a>), WireError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.remaining.iter().position(|&count| count > 0)?;
+        self.remaining[index] -= 1;
+
+        let section = [Section::Answer, Section::Authority, Section::Additional][index];
+
+        let record = parse::run(self.buf, &self.buf[self.offset..], parse::record(self.buf)).map(
+            |(offset, record)| {
+                self.offset = offset;
+                (section, record)
+            },
+        );
+
+        if record.is_err() {
+            self.remaining = [0; 3];
+        }
+
+        Some(record)
+    }
+}
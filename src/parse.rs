@@ -0,0 +1,270 @@
+use nom::{
+    bits::{bits, complete::take as take_bits},
+    bytes::complete::take,
+    combinator::{flat_map, map},
+    error::ErrorKind,
+    number::complete::{be_u16, be_u32, u8},
+    sequence::tuple,
+    ErrorConvert, IResult, Offset, Parser, ToUsize,
+};
+
+use crate::error::WireError;
+use crate::message_ref::{NameRef, QuestionRef, RecordRef};
+use crate::types::{
+    DomainName, Header, OperationCode, ResourceClass, ResourceType, ResponseCode, MAX_NAME_LENGTH,
+    MAX_POINTER_DEPTH,
+};
+
+pub(crate) const HEADER_LENGTH: usize = 12;
+
+/// Why a parser stopped. Running out of input only records what was left, which [`run`] turns
+/// into an offset once it knows where the message starts; anything else is already a
+/// [`WireError`].
+#[derive(Debug)]
+pub(crate) enum ParseError<'a> {
+    UnexpectedEnd(&'a [u8]),
+    Wire(WireError),
+}
+
+impl<'a> nom::error::ParseError<&'a [u8]> for ParseError<'a> {
+    fn from_error_kind(input: &'a [u8], _: ErrorKind) -> Self {
+        ParseError::UnexpectedEnd(input)
+    }
+
+    fn append(_: &'a [u8], _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> nom::error::ParseError<(&'a [u8], usize)> for ParseError<'a> {
+    fn from_error_kind((input, _): (&'a [u8], usize), _: ErrorKind) -> Self {
+        ParseError::UnexpectedEnd(input)
+    }
+
+    fn append(_: (&'a [u8], usize), _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> ErrorConvert<ParseError<'a>> for ParseError<'a> {
+    fn convert(self) -> Self {
+        self
+    }
+}
+
+pub(crate) type ParseResult<'a, T> = IResult<&'a [u8], T, ParseError<'a>>;
+
+/// Stops parsing with an error that can't be recovered from by trying something else.
+pub(crate) fn fail<'a, T>(error: WireError) -> ParseResult<'a, T> {
+    Err(nom::Err::Failure(ParseError::Wire(error)))
+}
+
+/// Runs a parser on `input`, which must be part of `message`, returning the offset in `message`
+/// where it stopped along with what it parsed.
+pub(crate) fn run<'a, T>(
+    message: &'a [u8],
+    input: &'a [u8],
+    mut parser: impl Parser<&'a [u8], T, ParseError<'a>>,
+) -> Result<(usize, T), WireError> {
+    match parser.parse(input) {
+        Ok((rest, value)) => Ok((message.offset(rest), value)),
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => Err(match error {
+            ParseError::UnexpectedEnd(rest) => WireError::UnexpectedEnd {
+                offset: message.offset(rest),
+            },
+            ParseError::Wire(error) => error,
+        }),
+        Err(nom::Err::Incomplete(_)) => Err(WireError::UnexpectedEnd {
+            offset: message.len(),
+        }),
+    }
+}
+
+/// Data prefixed by its length. nom's own `length_data` reports running out of input as
+/// incomplete rather than as an error at the data, which loses the offset.
+pub(crate) fn length_data<'a, N: ToUsize>(
+    length: impl Parser<&'a [u8], N, ParseError<'a>>,
+) -> impl FnMut(&'a [u8]) -> ParseResult<'a, &'a [u8]> {
+    flat_map(length, take)
+}
+
+fn flag(input: (&[u8], usize)) -> IResult<(&[u8], usize), bool, ParseError<'_>> {
+    map(take_bits(1usize), |bit: u8| bit == 1)(input)
+}
+
+pub(crate) fn header(input: &[u8]) -> ParseResult<'_, Header> {
+    let (input, id) = be_u16(input)?;
+    let (
+        input,
+        (
+            qr_indicator,
+            op_code,
+            authoritative_answer,
+            truncation,
+            recursion_desired,
+            recursion_available,
+            zero,
+            authentic_data,
+            checking_disabled,
+            response_code,
+        ),
+    ) = bits(tuple((
+        flag,
+        take_bits::<_, u8, _, _>(4usize),
+        flag,
+        flag,
+        flag,
+        flag,
+        flag,
+        flag,
+        flag,
+        take_bits::<_, u8, _, _>(4usize),
+    )))(input)?;
+    let (input, (question_count, answer_count, authority_count, additional_count)) =
+        tuple((be_u16, be_u16, be_u16, be_u16))(input)?;
+
+    Ok((
+        input,
+        Header {
+            id,
+            qr_indicator,
+            op_code: OperationCode::from(op_code),
+            authoritative_answer,
+            truncation,
+            recursion_desired,
+            recursion_available,
+            zero,
+            authentic_data,
+            checking_disabled,
+            // Only the low bits for now; the rest are filled in once the OPT record has been read
+            response_code: ResponseCode::from_parts(response_code, 0),
+            question_count,
+            answer_count,
+            authority_count,
+            additional_count,
+        },
+    ))
+}
+
+/// A possibly compressed name. Pointers are followed through `message`, and may only go back to
+/// a name seen earlier (RFC 1035 §4.1.4), never into the header. Requiring every hop to go
+/// backwards also means pointers can't loop.
+pub(crate) fn name<'a>(message: &'a [u8]) -> impl Fn(&'a [u8]) -> ParseResult<'a, NameRef<'a>> {
+    move |input| {
+        let start = message.offset(input);
+        let mut labels = input;
+        // Only the bytes up to the first pointer belong to this name's position
+        let mut end = None;
+        let mut name_length = 0;
+        let mut pointers = 0;
+
+        loop {
+            let offset = message.offset(labels);
+            let (after_length, length) = u8(labels)?;
+
+            match length & 0b1100_0000 {
+                0b1100_0000 => {
+                    let (after_pointer, pointer) = be_u16(labels)?;
+                    let target = (pointer & !0b1100_0000_0000_0000) as usize;
+
+                    if target >= message.len() {
+                        return fail(WireError::PointerOutOfBounds { offset, target });
+                    }
+
+                    if target < HEADER_LENGTH {
+                        return fail(WireError::PointerIntoHeader { offset, target });
+                    }
+
+                    if target >= offset {
+                        return fail(WireError::ForwardPointer { offset, target });
+                    }
+
+                    pointers += 1;
+                    if pointers > MAX_POINTER_DEPTH {
+                        return fail(WireError::PointerLoop { offset: start });
+                    }
+
+                    end.get_or_insert(after_pointer);
+                    labels = &message[target..];
+                }
+                0 => {
+                    let (after_label, _) = take(length)(after_length)?;
+
+                    name_length += length as usize + 1;
+                    if name_length > MAX_NAME_LENGTH {
+                        return fail(WireError::NameTooLong { offset: start });
+                    }
+
+                    labels = after_label;
+
+                    if length == 0 {
+                        break;
+                    }
+                }
+                _ => return fail(WireError::BadLabelLength { offset, length }),
+            }
+        }
+
+        let end = end.unwrap_or(labels);
+
+        Ok((
+            end,
+            NameRef {
+                buf: message,
+                offset: start,
+                wire_length: message.offset(end) - start,
+            },
+        ))
+    }
+}
+
+/// A name, copied out of the message.
+pub(crate) fn domain_name<'a>(
+    message: &'a [u8],
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, DomainName> {
+    move |input| {
+        let (rest, name) = name(message)(input)?;
+
+        match name.to_domain_name() {
+            Ok(name) => Ok((rest, name)),
+            Err(e) => fail(e),
+        }
+    }
+}
+
+pub(crate) fn question<'a>(
+    message: &'a [u8],
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, QuestionRef<'a>> {
+    move |input| {
+        map(
+            tuple((name(message), be_u16, be_u16)),
+            |(name, question_type, question_class)| QuestionRef {
+                name,
+                question_type: ResourceType::from(question_type),
+                question_class: ResourceClass::from(question_class),
+            },
+        )(input)
+    }
+}
+
+/// A resource record, with its RDATA left undecoded.
+pub(crate) fn record<'a>(message: &'a [u8]) -> impl Fn(&'a [u8]) -> ParseResult<'a, RecordRef<'a>> {
+    move |input| {
+        let (rest, (name, resource_type, class, time_to_live, rdata)) =
+            tuple((name(message), be_u16, be_u16, be_u32, length_data(be_u16)))(input)?;
+
+        Ok((
+            rest,
+            RecordRef {
+                buf: message,
+                offset: message.offset(input),
+                name,
+                resource_type: ResourceType::from(resource_type),
+                class: ResourceClass::from(class),
+                time_to_live,
+                rdata_offset: message.offset(rdata),
+                rdata_length: rdata.len() as u16,
+            },
+        ))
+    }
+}
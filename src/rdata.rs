@@ -0,0 +1,285 @@
+#![allow(clippy::upper_case_acronyms)]
+
+use std::{
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use bytes::BufMut;
+use nom::{
+    combinator::{eof, map, rest},
+    multi::many_till,
+    number::complete::{be_u128, be_u16, be_u32, u8},
+    sequence::tuple,
+    Offset,
+};
+
+use crate::error::WireError;
+use crate::parse::{self, domain_name, fail, length_data, ParseResult};
+use crate::types::{DomainName, MessageEncoder, ResourceType};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RData {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    CNAME(DomainName),
+    NS(DomainName),
+    PTR(DomainName),
+    MX {
+        preference: u16,
+        exchange: DomainName,
+    },
+    TXT(Vec<Vec<u8>>),
+    SOA {
+        mname: DomainName,
+        rname: DomainName,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: DomainName,
+    },
+    CAA {
+        flags: u8,
+        tag: String,
+        value: Vec<u8>,
+    },
+    Unknown(ResourceType, Vec<u8>),
+}
+
+impl RData {
+    /// Decodes `length` bytes of RDATA starting at `offset`. Names inside the RDATA may point
+    /// anywhere earlier in `message`, but must not run past the end of the record.
+    pub fn parse(
+        message: &[u8],
+        offset: usize,
+        length: u16,
+        resource_type: ResourceType,
+    ) -> Result<Self, WireError> {
+        let end = offset + length as usize;
+        let record = message
+            .get(..end)
+            .ok_or(WireError::UnexpectedEnd { offset })?;
+
+        let (position, data) = parse::run(record, &record[offset..], rdata(record, resource_type))?;
+
+        if position != end {
+            return Err(WireError::RDataLengthMismatch {
+                offset,
+                length,
+                consumed: position - offset,
+            });
+        }
+
+        Ok(data)
+    }
+
+    /// Writes the RDATA without its length prefix. Names are only compressed for the record
+    /// types defined in RFC 1035, as RFC 3597 forbids it for anything newer.
+    pub fn encode<'a>(&'a self, encoder: &mut MessageEncoder<'a, impl BufMut>) {
+        match self {
+            RData::A(address) => encoder.write_bytes(&address.octets()),
+            RData::AAAA(address) => encoder.write_bytes(&address.octets()),
+            RData::CNAME(name) | RData::NS(name) | RData::PTR(name) => encoder.write_name(name),
+            RData::MX {
+                preference,
+                exchange,
+            } => {
+                encoder.write_bytes(&preference.to_be_bytes());
+                encoder.write_name(exchange);
+            }
+            RData::TXT(strings) => {
+                for string in strings {
+                    encoder.write_character_string(string);
+                }
+            }
+            RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => {
+                encoder.write_name(mname);
+                encoder.write_name(rname);
+
+                for value in [serial, refresh, retry, expire, minimum] {
+                    encoder.write_bytes(&value.to_be_bytes());
+                }
+            }
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                encoder.write_bytes(&priority.to_be_bytes());
+                encoder.write_bytes(&weight.to_be_bytes());
+                encoder.write_bytes(&port.to_be_bytes());
+                encoder.write_uncompressed_name(target);
+            }
+            RData::CAA { flags, tag, value } => {
+                encoder.write_bytes(&[*flags]);
+                encoder.write_character_string(tag.as_bytes());
+                encoder.write_bytes(value);
+            }
+            RData::Unknown(_, data) => encoder.write_bytes(data),
+        }
+    }
+}
+
+/// Writes the RDATA in master file syntax. Types we don't know use the generic `\# length hex`
+/// form of RFC 3597 §5.
+impl fmt::Display for RData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RData::A(address) => write!(f, "{}", address),
+            RData::AAAA(address) => write!(f, "{}", address),
+            RData::CNAME(name) | RData::NS(name) | RData::PTR(name) => write!(f, "{}", name),
+            RData::MX {
+                preference,
+                exchange,
+            } => write!(f, "{} {}", preference, exchange),
+            RData::TXT(strings) => {
+                for (i, string) in strings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+
+                    write_character_string(f, string)?;
+                }
+
+                Ok(())
+            }
+            RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            RData::CAA { flags, tag, value } => {
+                write!(f, "{} {} ", flags, tag)?;
+                write_character_string(f, value)
+            }
+            RData::Unknown(_, data) => {
+                write!(f, "\\# {}", data.len())?;
+
+                if !data.is_empty() {
+                    write!(f, " ")?;
+                }
+
+                data.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+        }
+    }
+}
+
+// Quotes a <character-string>, escaping the quote and backslash and any unprintable bytes
+fn write_character_string(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for &byte in bytes {
+        match byte {
+            b'"' | b'\\' => write!(f, "\\{}", byte as char)?,
+            0x20..=0x7E => write!(f, "{}", byte as char)?,
+            _ => write!(f, "\\{:03}", byte)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+/// The parser for each record type's RDATA. Supporting a new type means adding its parser here.
+fn rdata<'a>(
+    message: &'a [u8],
+    resource_type: ResourceType,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, RData> {
+    move |input| match resource_type {
+        ResourceType::A => map(be_u32, |octets| RData::A(Ipv4Addr::from(octets)))(input),
+        ResourceType::AAAA => map(be_u128, |octets| RData::AAAA(Ipv6Addr::from(octets)))(input),
+        ResourceType::CNAME => map(domain_name(message), RData::CNAME)(input),
+        ResourceType::NS => map(domain_name(message), RData::NS)(input),
+        ResourceType::PTR => map(domain_name(message), RData::PTR)(input),
+        ResourceType::MX => map(
+            tuple((be_u16, domain_name(message))),
+            |(preference, exchange)| RData::MX {
+                preference,
+                exchange,
+            },
+        )(input),
+        ResourceType::TXT => map(many_till(length_data(u8), eof), |(strings, _)| {
+            RData::TXT(strings.into_iter().map(<[u8]>::to_vec).collect())
+        })(input),
+        ResourceType::SOA => map(
+            tuple((
+                domain_name(message),
+                domain_name(message),
+                be_u32,
+                be_u32,
+                be_u32,
+                be_u32,
+                be_u32,
+            )),
+            |(mname, rname, serial, refresh, retry, expire, minimum)| RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            },
+        )(input),
+        ResourceType::SRV => map(
+            tuple((be_u16, be_u16, be_u16, domain_name(message))),
+            |(priority, weight, port, target)| RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            },
+        )(input),
+        ResourceType::CAA => {
+            let (remaining, (flags, tag, value)) = tuple((u8, length_data(u8), rest))(input)?;
+
+            let Ok(tag) = String::from_utf8(tag.to_vec()) else {
+                return fail(WireError::InvalidRData {
+                    offset: message.offset(input),
+                });
+            };
+
+            Ok((
+                remaining,
+                RData::CAA {
+                    flags,
+                    tag,
+                    value: value.to_vec(),
+                },
+            ))
+        }
+        _ => map(rest, |data: &[u8]| {
+            RData::Unknown(resource_type, data.to_vec())
+        })(input),
+    }
+}
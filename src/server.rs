@@ -0,0 +1,210 @@
+use std::{net::Ipv4Addr, sync::Mutex};
+
+use bytes::BytesMut;
+
+use crate::cache::Cache;
+use crate::edns::Edns;
+use crate::message_ref::ParseOptions;
+use crate::rdata::RData;
+use crate::types::{Answer, Message, OperationCode, ResourceClass, ResourceType, ResponseCode};
+use crate::upstream::Upstream;
+use crate::zone::ZoneStore;
+
+// RFC 1035 §4.2.1: UDP messages are limited to 512 bytes unless the client says otherwise with EDNS
+const MAX_UDP_SIZE: usize = 512;
+
+const EDNS_VERSION: u8 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Udp,
+    Tcp,
+}
+
+/// Everything needed to answer a query. It is shared between the worker threads, so any state
+/// that changes while serving lives behind a lock.
+pub struct Server {
+    pub zones: ZoneStore,
+    pub cache: Mutex<Cache>,
+    pub upstream: Option<Upstream>,
+    pub compression: bool,
+}
+
+impl Server {
+    /// Decodes a query, resolves it and writes the encoded response onto the end of `response`.
+    pub fn handle(&self, query: &[u8], transport: Transport, response: &mut BytesMut) {
+        // Anything wrong with a query is the client's problem, so nothing is worked around
+        let received_message =
+            Message::parse(query, ParseOptions::strict()).map(|parsed| parsed.message);
+
+        match &received_message {
+            Ok(message) => println!("Received query:\n{}\n", message),
+            Err(e) => println!("Received malformed query: {}", e),
+        }
+
+        let mut max_size = match transport {
+            Transport::Udp => MAX_UDP_SIZE,
+            Transport::Tcp => u16::MAX as usize,
+        };
+
+        let mut response_message = match received_message {
+            Ok(received_message) => {
+                let edns = received_message.edns.clone();
+
+                match &edns {
+                    Some(edns) if transport == Transport::Udp => {
+                        max_size = edns.max_response_size();
+                    }
+                    _ => {}
+                }
+
+                let bad_version = edns
+                    .as_ref()
+                    .is_some_and(|edns| edns.version > EDNS_VERSION);
+
+                let mut response_message = if bad_version {
+                    Message::response_to(&received_message)
+                        .response_code(ResponseCode::BadVersion)
+                        .build()
+                } else {
+                    self.resolve(received_message)
+                };
+
+                response_message.edns = edns.as_ref().map(Edns::response_to);
+
+                response_message
+            }
+            Err(_) => {
+                // Echo the ID back if we managed to read one so the client can match the error
+                let id = if query.len() >= 2 {
+                    u16::from_be_bytes([query[0], query[1]])
+                } else {
+                    0
+                };
+
+                Message::builder()
+                    .id(id)
+                    .response(true)
+                    .response_code(ResponseCode::FormatError)
+                    .build()
+            }
+        };
+
+        response_message.truncate(max_size, self.compression);
+
+        println!("Sending response:\n{}\n", response_message);
+
+        let start = response.len();
+
+        if let Err(e) = response_message.encode_into(response, self.compression, max_size) {
+            // Truncating leaves at least the header and question, which always fit, so only
+            // records too long for the wire format get here
+            eprintln!("Unable to encode response: {}", e);
+            response.truncate(start);
+
+            let failure = Message::servfail(&response_message);
+
+            if failure
+                .encode_into(response, self.compression, max_size)
+                .is_err()
+            {
+                response.truncate(start);
+            }
+        }
+    }
+
+    pub fn resolve(&self, received_message: Message) -> Message {
+        let mut answers = Vec::new();
+        let mut authorities = Vec::new();
+        let mut additionals = Vec::new();
+        let mut authoritative_answer = false;
+        let mut response_code = ResponseCode::NoError;
+
+        if received_message.header.op_code != OperationCode::Query {
+            return Message::not_implemented(&received_message);
+        }
+
+        for question in &received_message.questions {
+            if let Some(zone) = self.zones.find(&question.name, question.question_class) {
+                println!("Answering from zone {}", zone.origin.to_unicode());
+
+                let lookup = zone.lookup(question);
+
+                authoritative_answer |= lookup.authoritative;
+                if response_code == ResponseCode::NoError {
+                    response_code = lookup.response_code;
+                }
+
+                answers.extend(lookup.answers);
+                authorities.extend(lookup.authorities);
+                additionals.extend(lookup.additionals);
+
+                continue;
+            }
+
+            if let Some(cached) = self.cache.lock().unwrap().get(question) {
+                println!("Answering from cache");
+
+                if response_code == ResponseCode::NoError {
+                    response_code = cached.response_code;
+                }
+
+                answers.extend(cached.answers);
+                authorities.extend(cached.authorities);
+                additionals.extend(cached.additionals);
+
+                continue;
+            }
+
+            match &self.upstream {
+                Some(upstream) => {
+                    println!("Resolving using resolver socket");
+
+                    match upstream.query(question, received_message.header.op_code) {
+                        Ok(resolver_response) => {
+                            println!("Resolver response:\n{}\n", resolver_response);
+
+                            self.cache
+                                .lock()
+                                .unwrap()
+                                .insert(question, &resolver_response);
+
+                            if response_code == ResponseCode::NoError {
+                                response_code = resolver_response.header.response_code;
+                            }
+
+                            answers.extend(resolver_response.answers);
+                            authorities.extend(resolver_response.authorities);
+                            additionals.extend(resolver_response.additionals);
+                        }
+                        Err(e) => {
+                            eprintln!("Unable to resolve {}: {}", question.name, e);
+
+                            response_code = ResponseCode::ServerFailure;
+                        }
+                    }
+                }
+                None if !self.zones.is_empty() => {
+                    response_code = ResponseCode::Refused;
+                }
+                None => {
+                    answers.push(Answer {
+                        name: question.name.clone(),
+                        resource_type: ResourceType::A,
+                        class: ResourceClass::IN,
+                        time_to_live: 60,
+                        data: RData::A(Ipv4Addr::new(8, 8, 8, 8)),
+                    });
+                }
+            }
+        }
+
+        Message::response_to(&received_message)
+            .authoritative(authoritative_answer)
+            .response_code(response_code)
+            .answers(answers)
+            .authorities(authorities)
+            .additionals(additionals)
+            .build()
+    }
+}
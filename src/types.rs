@@ -1,164 +1,712 @@
-#[derive(Debug)]
+#![allow(clippy::upper_case_acronyms)]
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use bytes::{BufMut, BytesMut};
+
+use crate::edns::Edns;
+use crate::error::{EncodeError, InvalidName, UnknownMnemonic, WireError};
+use crate::idna;
+use crate::message_ref::{MessageRef, ParseOptions, ParsedMessage};
+use crate::parse;
+use crate::rdata::RData;
+
+pub(crate) const MAX_NAME_LENGTH: usize = 255;
+const MAX_LABEL_LENGTH: usize = 63;
+pub(crate) const MAX_POINTER_DEPTH: usize = 127;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Message {
     pub header: Header,
     pub questions: Vec<Question>,
     pub answers: Vec<Answer>,
+    pub authorities: Vec<Answer>,
+    pub additionals: Vec<Answer>,
+    pub edns: Option<Edns>,
 }
 
-impl From<Message> for Vec<u8> {
-    fn from(val: Message) -> Self {
-        let mut res = Vec::new();
+impl Message {
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::default()
+    }
 
-        let header: [u8; 12] = val.header.into();
+    /// Starts a response to `query`, carrying over the ID, opcode, RD and CD bits (RFC 4035
+    /// §3.2.2) and questions.
+    pub fn response_to(query: &Message) -> MessageBuilder {
+        Message::builder()
+            .id(query.header.id)
+            .response(true)
+            .op_code(query.header.op_code)
+            .recursion_desired(query.header.recursion_desired)
+            .checking_disabled(query.header.checking_disabled)
+            .questions(query.questions.iter().cloned())
+    }
+
+    pub fn nxdomain(query: &Message) -> Message {
+        Message::response_to(query)
+            .response_code(ResponseCode::NameError)
+            .build()
+    }
 
-        res.extend_from_slice(&header);
+    pub fn servfail(query: &Message) -> Message {
+        Message::response_to(query)
+            .response_code(ResponseCode::ServerFailure)
+            .build()
+    }
 
-        for question in val.questions {
-            let question: Vec<u8> = question.into();
-            res.extend_from_slice(&question);
+    pub fn refused(query: &Message) -> Message {
+        Message::response_to(query)
+            .response_code(ResponseCode::Refused)
+            .build()
+    }
+
+    pub fn not_implemented(query: &Message) -> Message {
+        Message::response_to(query)
+            .response_code(ResponseCode::NotImplemented)
+            .build()
+    }
+
+    /// Encodes the message onto the end of `buf`, writing into it directly. The section counts
+    /// in the header are taken from the sections themselves, so they can't disagree with what is
+    /// written. If the message would be longer than `max_size` an error is returned, and `buf` is
+    /// left holding only the part that fit. A section or RDATA too long for its 16-bit length is
+    /// an error too, rather than being written with the length cut short.
+    pub fn encode_into(
+        &self,
+        buf: &mut impl BufMut,
+        compression: bool,
+        max_size: usize,
+    ) -> Result<usize, EncodeError> {
+        let opt = self
+            .edns
+            .as_ref()
+            .map(|edns| edns.to_record(self.header.response_code))
+            .transpose()?;
+
+        let count =
+            |count: usize| u16::try_from(count).map_err(|_| EncodeError::TooManyEntries { count });
+
+        let header = Header {
+            question_count: count(self.questions.len())?,
+            answer_count: count(self.answers.len())?,
+            authority_count: count(self.authorities.len())?,
+            additional_count: count(self.additionals.len() + opt.is_some() as usize)?,
+            ..self.header.clone()
+        };
+
+        let mut encoder = MessageEncoder::new(buf, compression, max_size);
+
+        encoder.write_header(header);
+
+        for question in &self.questions {
+            encoder.write_question(question);
         }
 
-        for answer in val.answers {
-            let answer: Vec<u8> = answer.into();
-            res.extend_from_slice(&answer);
+        for answer in self
+            .answers
+            .iter()
+            .chain(&self.authorities)
+            .chain(&self.additionals)
+            .chain(&opt)
+        {
+            encoder.write_answer(answer);
         }
 
-        res
+        encoder.finish()
+    }
+
+    /// Encodes with no size limit. Panics if a section or some RDATA is too long for the wire
+    /// format, which can't happen to a message that was decoded from it.
+    pub fn encode(&self, compression: bool) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        self.encode_into(&mut buf, compression, usize::MAX)
+            .expect("there is no size limit");
+
+        buf
+    }
+
+    /// Drops whole RRsets from the end of the message until it encodes to at most `max_size`
+    /// bytes. TC is only set once answer or authority data has to go, as the additional section
+    /// is optional (RFC 2181 §9).
+    pub fn truncate(&mut self, max_size: usize, compression: bool) {
+        // Each attempt stops writing at `max_size`, so one buffer that size is enough for all of them
+        let mut buf = BytesMut::with_capacity(max_size.min(u16::MAX as usize));
+
+        loop {
+            buf.clear();
+
+            match self.encode_into(&mut buf, compression, max_size) {
+                Err(EncodeError::TooLong { .. }) => {}
+                // Dropping records can't make anything else fit
+                _ => break,
+            }
+
+            let section = if !self.additionals.is_empty() {
+                &mut self.additionals
+            } else if !self.authorities.is_empty() {
+                self.header.truncation = true;
+                &mut self.authorities
+            } else if !self.answers.is_empty() {
+                self.header.truncation = true;
+                &mut self.answers
+            } else {
+                break;
+            };
+
+            let last = section.last().unwrap();
+            let rrset = (last.name.clone(), last.resource_type, last.class);
+
+            while section.last().is_some_and(|record| {
+                record.name == rrset.0 && record.resource_type == rrset.1 && record.class == rrset.2
+            }) {
+                section.pop();
+            }
+        }
     }
 }
 
-impl TryFrom<Vec<u8>> for Message {
-    type Error = ();
+/// Assembles a message section by section. Header counts are left for [`Message::encode`] to
+/// fill in.
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    header: Header,
+    questions: Vec<Question>,
+    answers: Vec<Answer>,
+    authorities: Vec<Answer>,
+    additionals: Vec<Answer>,
+    edns: Option<Edns>,
+}
 
-    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        if value.len() < 12 {
-            panic!("Unable to parse message as it is too short")
+impl MessageBuilder {
+    pub fn id(mut self, id: u16) -> Self {
+        self.header.id = id;
+        self
+    }
+
+    pub fn response(mut self, response: bool) -> Self {
+        self.header.qr_indicator = response;
+        self
+    }
+
+    pub fn op_code(mut self, op_code: OperationCode) -> Self {
+        self.header.op_code = op_code;
+        self
+    }
+
+    pub fn authoritative(mut self, authoritative: bool) -> Self {
+        self.header.authoritative_answer = authoritative;
+        self
+    }
+
+    pub fn truncated(mut self, truncated: bool) -> Self {
+        self.header.truncation = truncated;
+        self
+    }
+
+    pub fn recursion_desired(mut self, recursion_desired: bool) -> Self {
+        self.header.recursion_desired = recursion_desired;
+        self
+    }
+
+    pub fn recursion_available(mut self, recursion_available: bool) -> Self {
+        self.header.recursion_available = recursion_available;
+        self
+    }
+
+    pub fn authentic_data(mut self, authentic_data: bool) -> Self {
+        self.header.authentic_data = authentic_data;
+        self
+    }
+
+    pub fn checking_disabled(mut self, checking_disabled: bool) -> Self {
+        self.header.checking_disabled = checking_disabled;
+        self
+    }
+
+    pub fn response_code(mut self, response_code: ResponseCode) -> Self {
+        self.header.response_code = response_code;
+        self
+    }
+
+    pub fn question(mut self, question: Question) -> Self {
+        self.questions.push(question);
+        self
+    }
+
+    pub fn questions(mut self, questions: impl IntoIterator<Item = Question>) -> Self {
+        self.questions.extend(questions);
+        self
+    }
+
+    pub fn answers(mut self, answers: impl IntoIterator<Item = Answer>) -> Self {
+        self.answers.extend(answers);
+        self
+    }
+
+    pub fn authorities(mut self, authorities: impl IntoIterator<Item = Answer>) -> Self {
+        self.authorities.extend(authorities);
+        self
+    }
+
+    pub fn additionals(mut self, additionals: impl IntoIterator<Item = Answer>) -> Self {
+        self.additionals.extend(additionals);
+        self
+    }
+
+    pub fn edns(mut self, edns: Option<Edns>) -> Self {
+        self.edns = edns;
+        self
+    }
+
+    pub fn build(self) -> Message {
+        Message {
+            header: Header {
+                question_count: self.questions.len() as u16,
+                answer_count: self.answers.len() as u16,
+                authority_count: self.authorities.len() as u16,
+                additional_count: self.additionals.len() as u16 + self.edns.is_some() as u16,
+                ..self.header
+            },
+            questions: self.questions,
+            answers: self.answers,
+            authorities: self.authorities,
+            additionals: self.additionals,
+            edns: self.edns,
+        }
+    }
+}
+
+/// Writes the message the way dig does, for logging and debugging tools.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            ";; ->>HEADER<<- opcode: {}, status: {}, id: {}",
+            self.header.op_code, self.header.response_code, self.header.id
+        )?;
+        write!(
+            f,
+            ";; flags: {}; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+            self.header.flags().join(" "),
+            self.questions.len(),
+            self.answers.len(),
+            self.authorities.len(),
+            self.additionals.len() + self.edns.is_some() as usize
+        )?;
+
+        if let Some(edns) = &self.edns {
+            write!(f, "\n\n;; OPT PSEUDOSECTION:\n{}", edns)?;
+        }
+
+        if !self.questions.is_empty() {
+            write!(f, "\n\n;; QUESTION SECTION:")?;
+
+            for question in &self.questions {
+                write!(f, "\n{}", question)?;
+            }
+        }
+
+        for (title, records) in [
+            ("ANSWER", &self.answers),
+            ("AUTHORITY", &self.authorities),
+            ("ADDITIONAL", &self.additionals),
+        ] {
+            if records.is_empty() {
+                continue;
+            }
+
+            write!(f, "\n\n;; {} SECTION:", title)?;
+
+            for record in records {
+                write!(f, "\n{}", record)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Message> for Vec<u8> {
+    fn from(val: Message) -> Self {
+        val.encode(true)
+    }
+}
+
+// Pointers only have 14 bits to address the target, so names written later than this can't be
+// referenced.
+const MAX_POINTER_OFFSET: usize = 0x3FFF;
+
+/// Writes a message straight into a buffer. Names are compressed against those written earlier,
+/// which are remembered by the labels they were written from rather than read back out of the
+/// buffer. Anything past `max_size` is not written, and [`MessageEncoder::finish`] reports it.
+pub struct MessageEncoder<'a, B> {
+    buf: B,
+    compression: bool,
+    names: Vec<(&'a [Label], u16)>,
+    position: usize,
+    max_size: usize,
+    error: Option<EncodeError>,
+    // While working out how long some RDATA is, bytes are counted but not written
+    measuring: bool,
+}
+
+impl<'a, B: BufMut> MessageEncoder<'a, B> {
+    pub fn new(buf: B, compression: bool, max_size: usize) -> Self {
+        MessageEncoder {
+            buf,
+            compression,
+            names: Vec::new(),
+            position: 0,
+            max_size,
+            error: None,
+            measuring: false,
+        }
+    }
+
+    pub fn write_header(&mut self, header: Header) {
+        let header: [u8; 12] = header.into();
+
+        self.write_bytes(&header);
+    }
+
+    pub fn write_question(&mut self, question: &'a Question) {
+        self.write_name(&question.name);
+        self.write_bytes(&u16::from(question.question_type).to_be_bytes());
+        self.write_bytes(&u16::from(question.question_class).to_be_bytes());
+    }
+
+    pub fn write_answer(&mut self, answer: &'a Answer) {
+        self.write_name(&answer.name);
+        self.write_bytes(&u16::from(answer.resource_type).to_be_bytes());
+        self.write_bytes(&u16::from(answer.class).to_be_bytes());
+        self.write_bytes(&answer.time_to_live.to_be_bytes());
+
+        // The length comes first and can't be filled in afterwards, so encode the RDATA once
+        // without writing it to find out how long it is
+        let length = self.measure(|encoder| answer.data.encode(encoder));
+
+        let Ok(length) = u16::try_from(length) else {
+            self.error
+                .get_or_insert(EncodeError::RDataTooLong { length });
+            return;
         };
 
-        let mut offset = 0;
+        self.write_bytes(&length.to_be_bytes());
+        answer.data.encode(self);
+    }
+
+    // Starts from where the RDATA will really be written, so compression makes the same choices
+    // both times
+    fn measure(&mut self, encode: impl FnOnce(&mut Self)) -> usize {
+        let (position, names, measuring) = (self.position, self.names.len(), self.measuring);
 
-        let header = &value[offset..12].to_vec();
-        let mut header_slice: [u8; 12] = [0; 12];
-        header_slice.clone_from_slice(header);
-        let header = Header::try_from(header_slice)?;
+        self.position += 2;
+        self.measuring = true;
+        encode(self);
 
-        offset += 12;
+        let length = self.position - position - 2;
 
-        let mut questions = Vec::with_capacity(header.question_count as usize);
+        self.position = position;
+        self.names.truncate(names);
+        self.measuring = measuring;
 
-        for _ in 0..header.question_count {
-            let name = DomainName::parse(&value[offset..].to_vec(), &value);
-            offset += name.byte_size as usize;
+        length
+    }
 
-            let question_type = (value[offset] as u16) << 8 | value[offset + 1] as u16;
-            let question_type = QuestionType::try_from(question_type)?;
-            offset += 2;
+    /// Writes `bytes` after a one-byte length, as RFC 1035 §3.3 lays out a <character-string>.
+    pub fn write_character_string(&mut self, bytes: &[u8]) {
+        let Ok(length) = u8::try_from(bytes.len()) else {
+            self.error
+                .get_or_insert(EncodeError::CharacterStringTooLong {
+                    length: bytes.len(),
+                });
+            return;
+        };
 
-            let question_class = (value[offset] as u16) << 8 | value[offset + 1] as u16;
-            let question_class = QuestionClass::try_from(question_class)?;
-            offset += 2;
+        self.write_bytes(&[length]);
+        self.write_bytes(bytes);
+    }
 
-            let question = Question {
-                name,
-                question_type,
-                question_class,
-            };
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
 
-            questions.push(question);
+        if !self.measuring {
+            if self.position + bytes.len() > self.max_size {
+                self.error = Some(EncodeError::TooLong {
+                    max_size: self.max_size,
+                });
+                return;
+            }
+
+            self.buf.put_slice(bytes);
         }
 
-        let mut answers = Vec::with_capacity(header.answer_count as usize);
+        self.position += bytes.len();
+    }
 
-        for _ in 0..header.answer_count {
-            let name = DomainName::parse(&value[offset..].to_vec(), &value);
-            offset += name.byte_size as usize;
+    pub fn write_uncompressed_name(&mut self, name: &'a DomainName) {
+        let compression = self.compression;
 
-            let resource_type = (value[offset] as u16) << 8 | value[offset + 1] as u16;
-            let resource_type = ResourceType::try_from(resource_type)?;
-            offset += 2;
+        self.compression = false;
+        self.write_name(name);
+        self.compression = compression;
+    }
 
-            let resource_class = (value[offset] as u16) << 8 | value[offset + 1] as u16;
-            let resource_class: ResourceClass = ResourceClass::try_from(resource_class)?;
-            offset += 2;
+    pub fn write_name(&mut self, name: &'a DomainName) {
+        let labels = name.labels.as_slice();
 
-            let time_to_live = (value[offset] as u32) << 24
-                | (value[offset + 1] as u32) << 16
-                | (value[offset + 2] as u32) << 8
-                | value[offset + 3] as u32;
-            offset += 4;
+        for i in 0..labels.len() {
+            let suffix = &labels[i..];
 
-            let length = (value[offset] as u16) << 8 | value[offset + 1] as u16;
-            offset += 2;
+            if self.compression {
+                // Names are matched byte for byte, so pointing at one doesn't change its case
+                let written = self.names.iter().find(|(name, _)| {
+                    name.len() == suffix.len() && name.iter().zip(suffix).all(|(a, b)| a.0 == b.0)
+                });
 
-            let data = value[offset..(offset + length as usize)].to_vec();
+                if let Some(&(_, pointer)) = written {
+                    self.write_bytes(&(0b1100_0000_0000_0000 | pointer).to_be_bytes());
+                    return;
+                }
 
-            let answer = Answer {
-                name,
-                resource_type,
-                class: resource_class,
-                time_to_live,
-                length,
-                data,
-            };
+                if self.position <= MAX_POINTER_OFFSET {
+                    self.names.push((suffix, self.position as u16));
+                }
+            }
 
-            answers.push(answer);
+            self.write_bytes(&[labels[i].0.len() as u8]);
+            self.write_bytes(&labels[i].0);
         }
 
-        Ok(Message {
-            header,
-            questions,
-            answers,
-        })
+        self.write_bytes(&[0]);
+    }
+
+    /// The number of bytes written, or the first error that stopped writing.
+    pub fn finish(self) -> Result<usize, EncodeError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.position),
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Message {
+    /// Decodes a message, checking it as closely as `options` asks.
+    pub fn parse(buf: &[u8], options: ParseOptions) -> Result<ParsedMessage, WireError> {
+        MessageRef::parse(buf)?.to_message_with(options)
+    }
+}
+
+impl TryFrom<&[u8]> for Message {
+    type Error = WireError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        MessageRef::parse(value)?.to_message()
+    }
+}
+
+impl TryFrom<Vec<u8>> for Message {
+    type Error = WireError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Message::try_from(value.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum OperationCode {
-    Query = 0,
-    IQuery = 1,
-    Status = 2,
+    #[default]
+    Query,
+    IQuery,
+    Status,
+    Notify,
+    Update,
+    Dso,
+    Unknown(u8),
 }
 
-impl TryFrom<u8> for OperationCode {
-    type Error = ();
+impl From<u8> for OperationCode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OperationCode::Query,
+            1 => OperationCode::IQuery,
+            2 => OperationCode::Status,
+            4 => OperationCode::Notify,
+            5 => OperationCode::Update,
+            6 => OperationCode::Dso,
+            _ => OperationCode::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<OperationCode> for u8 {
+    fn from(value: OperationCode) -> Self {
         match value {
-            0 => Ok(OperationCode::Query),
-            1 => Ok(OperationCode::IQuery),
-            2 => Ok(OperationCode::Status),
-            _ => Err(()),
+            OperationCode::Query => 0,
+            OperationCode::IQuery => 1,
+            OperationCode::Status => 2,
+            OperationCode::Notify => 4,
+            OperationCode::Update => 5,
+            OperationCode::Dso => 6,
+            OperationCode::Unknown(value) => value,
         }
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for OperationCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationCode::Query => write!(f, "QUERY"),
+            OperationCode::IQuery => write!(f, "IQUERY"),
+            OperationCode::Status => write!(f, "STATUS"),
+            OperationCode::Notify => write!(f, "NOTIFY"),
+            OperationCode::Update => write!(f, "UPDATE"),
+            OperationCode::Dso => write!(f, "DSO"),
+            OperationCode::Unknown(value) => write!(f, "OPCODE{}", value),
+        }
+    }
+}
+
+/// A 12 bit response code. Only the low 4 bits fit in the header; the rest travel in the OPT
+/// record (RFC 6891 §6.1.3), so codes above 15 can only be sent to clients using EDNS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ResponseCode {
-    NoError = 0,
-    FormatError = 1,
-    ServerFailure = 2,
-    NameError = 3,
-    NotImplemented = 4,
-    Refused = 5,
+    #[default]
+    NoError,
+    FormatError,
+    ServerFailure,
+    NameError,
+    NotImplemented,
+    Refused,
+    YXDomain,
+    YXRRSet,
+    NXRRSet,
+    NotAuth,
+    NotZone,
+    DsoTypeNotImplemented,
+    /// BADVERS, or BADSIG inside a TSIG record, which shares the value
+    BadVersion,
+    BadKey,
+    BadTime,
+    BadMode,
+    BadName,
+    BadAlgorithm,
+    BadTruncation,
+    BadCookie,
+    Unknown(u16),
+}
+
+impl ResponseCode {
+    /// The bits that go in the header.
+    pub fn low_bits(self) -> u8 {
+        (u16::from(self) & 0x0F) as u8
+    }
+
+    /// The bits that go in the OPT record.
+    pub fn extended_bits(self) -> u8 {
+        (u16::from(self) >> 4) as u8
+    }
+
+    pub fn from_parts(low_bits: u8, extended_bits: u8) -> Self {
+        ResponseCode::from((extended_bits as u16) << 4 | (low_bits & 0x0F) as u16)
+    }
 }
 
-impl TryFrom<u8> for ResponseCode {
-    type Error = ();
+impl From<u16> for ResponseCode {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => ResponseCode::NoError,
+            1 => ResponseCode::FormatError,
+            2 => ResponseCode::ServerFailure,
+            3 => ResponseCode::NameError,
+            4 => ResponseCode::NotImplemented,
+            5 => ResponseCode::Refused,
+            6 => ResponseCode::YXDomain,
+            7 => ResponseCode::YXRRSet,
+            8 => ResponseCode::NXRRSet,
+            9 => ResponseCode::NotAuth,
+            10 => ResponseCode::NotZone,
+            11 => ResponseCode::DsoTypeNotImplemented,
+            16 => ResponseCode::BadVersion,
+            17 => ResponseCode::BadKey,
+            18 => ResponseCode::BadTime,
+            19 => ResponseCode::BadMode,
+            20 => ResponseCode::BadName,
+            21 => ResponseCode::BadAlgorithm,
+            22 => ResponseCode::BadTruncation,
+            23 => ResponseCode::BadCookie,
+            _ => ResponseCode::Unknown(value),
+        }
+    }
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<ResponseCode> for u16 {
+    fn from(value: ResponseCode) -> Self {
         match value {
-            0 => Ok(ResponseCode::NoError),
-            1 => Ok(ResponseCode::FormatError),
-            2 => Ok(ResponseCode::ServerFailure),
-            3 => Ok(ResponseCode::NameError),
-            4 => Ok(ResponseCode::NotImplemented),
-            5 => Ok(ResponseCode::Refused),
-            _ => Err(()),
+            ResponseCode::NoError => 0,
+            ResponseCode::FormatError => 1,
+            ResponseCode::ServerFailure => 2,
+            ResponseCode::NameError => 3,
+            ResponseCode::NotImplemented => 4,
+            ResponseCode::Refused => 5,
+            ResponseCode::YXDomain => 6,
+            ResponseCode::YXRRSet => 7,
+            ResponseCode::NXRRSet => 8,
+            ResponseCode::NotAuth => 9,
+            ResponseCode::NotZone => 10,
+            ResponseCode::DsoTypeNotImplemented => 11,
+            ResponseCode::BadVersion => 16,
+            ResponseCode::BadKey => 17,
+            ResponseCode::BadTime => 18,
+            ResponseCode::BadMode => 19,
+            ResponseCode::BadName => 20,
+            ResponseCode::BadAlgorithm => 21,
+            ResponseCode::BadTruncation => 22,
+            ResponseCode::BadCookie => 23,
+            ResponseCode::Unknown(value) => value,
         }
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for ResponseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match self {
+            ResponseCode::NoError => "NOERROR",
+            ResponseCode::FormatError => "FORMERR",
+            ResponseCode::ServerFailure => "SERVFAIL",
+            ResponseCode::NameError => "NXDOMAIN",
+            ResponseCode::NotImplemented => "NOTIMP",
+            ResponseCode::Refused => "REFUSED",
+            ResponseCode::YXDomain => "YXDOMAIN",
+            ResponseCode::YXRRSet => "YXRRSET",
+            ResponseCode::NXRRSet => "NXRRSET",
+            ResponseCode::NotAuth => "NOTAUTH",
+            ResponseCode::NotZone => "NOTZONE",
+            ResponseCode::DsoTypeNotImplemented => "DSOTYPENI",
+            ResponseCode::BadVersion => "BADVERS",
+            ResponseCode::BadKey => "BADKEY",
+            ResponseCode::BadTime => "BADTIME",
+            ResponseCode::BadMode => "BADMODE",
+            ResponseCode::BadName => "BADNAME",
+            ResponseCode::BadAlgorithm => "BADALG",
+            ResponseCode::BadTruncation => "BADTRUNC",
+            ResponseCode::BadCookie => "BADCOOKIE",
+            ResponseCode::Unknown(value) => return write!(f, "RCODE{}", value),
+        };
+
+        write!(f, "{}", mnemonic)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Header {
     pub id: u16,
     pub qr_indicator: bool,
@@ -167,6 +715,10 @@ pub struct Header {
     pub truncation: bool,
     pub recursion_desired: bool,
     pub recursion_available: bool,
+    /// The reserved Z bit. It should be zero, but is kept so a message round-trips unchanged.
+    pub zero: bool,
+    pub authentic_data: bool,
+    pub checking_disabled: bool,
     pub response_code: ResponseCode,
     pub question_count: u16,
     pub answer_count: u16,
@@ -174,6 +726,25 @@ pub struct Header {
     pub additional_count: u16,
 }
 
+impl Header {
+    /// The names of the flags that are set, as dig lists them.
+    fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.qr_indicator, "qr"),
+            (self.authoritative_answer, "aa"),
+            (self.truncation, "tc"),
+            (self.recursion_desired, "rd"),
+            (self.recursion_available, "ra"),
+            (self.zero, "z"),
+            (self.authentic_data, "ad"),
+            (self.checking_disabled, "cd"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect()
+    }
+}
+
 impl From<Header> for [u8; 12] {
     fn from(val: Header) -> Self {
         let mut res = [0; 12];
@@ -181,11 +752,15 @@ impl From<Header> for [u8; 12] {
         res[0] = val.id.to_be_bytes()[0];
         res[1] = val.id.to_be_bytes()[1];
         res[2] = (val.qr_indicator as u8) << 7
-            | (val.op_code as u8) << 3
+            | (u8::from(val.op_code) & 0b00001111) << 3
             | (val.authoritative_answer as u8) << 2
             | (val.truncation as u8) << 1
             | (val.recursion_desired as u8);
-        res[3] = (val.recursion_available as u8) << 7 | (val.response_code as u8);
+        res[3] = (val.recursion_available as u8) << 7
+            | (val.zero as u8) << 6
+            | (val.authentic_data as u8) << 5
+            | (val.checking_disabled as u8) << 4
+            | val.response_code.low_bits();
         res[4] = val.question_count.to_be_bytes()[0];
         res[5] = val.question_count.to_be_bytes()[1];
         res[6] = val.answer_count.to_be_bytes()[0];
@@ -199,337 +774,1435 @@ impl From<Header> for [u8; 12] {
     }
 }
 
-impl TryFrom<[u8; 12]> for Header {
-    type Error = ();
-
-    fn try_from(value: [u8; 12]) -> Result<Self, Self::Error> {
-        let id: u16 = (value[0] as u16) << 8 | (value[1] as u16);
-        let qr_indicator = (value[2] >> 7 & 1) == 1;
-
-        let op_code = OperationCode::try_from(value[2] >> 3 & 0b00001111)?;
-        let authoritative_answer = (value[2] >> 2 & 1) == 1;
-        let truncation = (value[2] >> 1 & 1) == 1;
-        let recursion_desired = (value[2] & 1) == 1;
-        let recursion_available = (value[3] >> 7 & 1) == 1;
-        let response_code = ResponseCode::try_from(value[3] & 0b00001111)?;
-        let question_count = (value[4] as u16) << 8 | (value[5] as u16);
-        let answer_count = (value[6] as u16) << 8 | (value[7] as u16);
-        let authority_count = (value[8] as u16) << 8 | (value[9] as u16);
-        let additional_count = (value[10] as u16) << 8 | (value[11] as u16);
-
-        Ok(Header {
-            id,
-            qr_indicator,
-            op_code,
-            authoritative_answer,
-            truncation,
-            recursion_desired,
-            recursion_available,
-            response_code,
-            question_count,
-            answer_count,
-            authority_count,
-            additional_count,
-        })
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum QuestionType {
-    A = 1,
-    NS = 2,
-    MD = 3,
-    MF = 4,
-    CNAME = 5,
-    SOA = 6,
-    MB = 7,
-    MG = 8,
-    MR = 9,
-    NULL = 10,
-    WKS = 11,
-    PTR = 12,
-    HINFO = 13,
-    MINFO = 14,
-    MX = 15,
-    TXT = 16,
-    AXFR = 252,
-    MAILB = 253,
-    MAILA = 254,
-    ALL = 255,
-}
-
-impl TryFrom<u16> for QuestionType {
-    type Error = ();
-
-    fn try_from(value: u16) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(QuestionType::A),
-            2 => Ok(QuestionType::NS),
-            3 => Ok(QuestionType::MD),
-            4 => Ok(QuestionType::MF),
-            5 => Ok(QuestionType::CNAME),
-            6 => Ok(QuestionType::SOA),
-            7 => Ok(QuestionType::MB),
-            8 => Ok(QuestionType::MG),
-            9 => Ok(QuestionType::MR),
-            10 => Ok(QuestionType::NULL),
-            11 => Ok(QuestionType::WKS),
-            12 => Ok(QuestionType::PTR),
-            13 => Ok(QuestionType::HINFO),
-            14 => Ok(QuestionType::MINFO),
-            15 => Ok(QuestionType::MX),
-            16 => Ok(QuestionType::TXT),
-            252 => Ok(QuestionType::AXFR),
-            253 => Ok(QuestionType::MAILB),
-            254 => Ok(QuestionType::MAILA),
-            255 => Ok(QuestionType::ALL),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum QuestionClass {
-    IN = 1,
-    CS = 2,
-    CH = 3,
-    HS = 4,
-    ANY = 255,
-}
-
-impl TryFrom<u16> for QuestionClass {
-    type Error = ();
-
-    fn try_from(value: u16) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(QuestionClass::IN),
-            2 => Ok(QuestionClass::CS),
-            3 => Ok(QuestionClass::CH),
-            4 => Ok(QuestionClass::HS),
-            255 => Ok(QuestionClass::ANY),
-            _ => Err(()),
-        }
+impl From<[u8; 12]> for Header {
+    fn from(value: [u8; 12]) -> Self {
+        let (_, header) = parse::header(&value).expect("a header is always 12 bytes");
+
+        header
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Question {
     pub name: DomainName,
-    pub question_type: QuestionType,
-    pub question_class: QuestionClass,
+    pub question_type: ResourceType,
+    pub question_class: ResourceClass,
+}
+
+impl fmt::Display for Question {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ";{}\t\t{}\t{}",
+            self.name, self.question_class, self.question_type
+        )
+    }
 }
 
 impl From<Question> for Vec<u8> {
     fn from(val: Question) -> Self {
-        let mut res = Vec::new();
+        let mut buf = Vec::new();
+        let mut encoder = MessageEncoder::new(&mut buf, false, usize::MAX);
 
-        let name = val.name.encode();
-        let question_type = (val.question_type as u16).to_be_bytes();
-        let class = (val.question_class as u16).to_be_bytes();
+        encoder.write_question(&val);
+        encoder.finish().expect("there is no size limit");
 
-        res.extend_from_slice(&name);
-        res.extend_from_slice(&question_type);
-        res.extend_from_slice(&class);
-
-        res
+        buf
     }
 }
 
-#[derive(Debug)]
-pub enum ResourceType {
-    A = 1,
-    NS = 2,
-    MD = 3,
-    MF = 4,
-    CNAME = 5,
-    SOA = 6,
-    MB = 7,
-    MG = 8,
-    MR = 9,
-    NULL = 10,
-    WKS = 11,
-    PTR = 12,
-    HINFO = 13,
-    MINFO = 14,
-    MX = 15,
-    TXT = 16,
-}
+// Generates a code point enum along with lossless conversions to and from its numeric value and its
+// mnemonic. Values without a variant are kept in `Unknown` so they can be passed through untouched,
+// and are written as the generic `TYPE1234` style mnemonic (RFC 3597).
+macro_rules! code_points {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident: $repr:ty as $generic:literal {
+        $($variant:ident = $value:literal,)*
+    }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant,)*
+            Unknown($repr),
+        }
 
-impl TryFrom<u16> for ResourceType {
-    type Error = ();
+        impl From<$repr> for $name {
+            fn from(value: $repr) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
 
-    fn try_from(value: u16) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(ResourceType::A),
-            2 => Ok(ResourceType::NS),
-            3 => Ok(ResourceType::MD),
-            4 => Ok(ResourceType::MF),
-            5 => Ok(ResourceType::CNAME),
-            6 => Ok(ResourceType::SOA),
-            7 => Ok(ResourceType::MB),
-            8 => Ok(ResourceType::MG),
-            9 => Ok(ResourceType::MR),
-            10 => Ok(ResourceType::NULL),
-            11 => Ok(ResourceType::WKS),
-            12 => Ok(ResourceType::PTR),
-            13 => Ok(ResourceType::HINFO),
-            14 => Ok(ResourceType::MINFO),
-            15 => Ok(ResourceType::MX),
-            16 => Ok(ResourceType::TXT),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Debug)]
-pub enum ResourceClass {
-    IN = 1,
-    CS = 2,
-    CH = 3,
-    HS = 4,
-}
-
-impl TryFrom<u16> for ResourceClass {
-    type Error = ();
-
-    fn try_from(value: u16) -> Result<Self, Self::Error> {
-        match value {
-            1 => Ok(ResourceClass::IN),
-            2 => Ok(ResourceClass::CS),
-            3 => Ok(ResourceClass::CH),
-            4 => Ok(ResourceClass::HS),
-            _ => Err(()),
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $($name::$variant => write!(f, stringify!($variant)),)*
+                    $name::Unknown(value) => write!(f, concat!($generic, "{}"), value),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnknownMnemonic;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let upper = value.to_ascii_uppercase();
+
+                match upper.as_str() {
+                    $(stringify!($variant) => Ok($name::$variant),)*
+                    _ => upper
+                        .strip_prefix($generic)
+                        .and_then(|number| number.parse::<$repr>().ok())
+                        .map($name::from)
+                        .ok_or_else(|| UnknownMnemonic(value.to_string())),
+                }
+            }
         }
+    };
+}
+
+code_points! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ResourceType: u16 as "TYPE" {
+        A = 1,
+        NS = 2,
+        MD = 3,
+        MF = 4,
+        CNAME = 5,
+        SOA = 6,
+        MB = 7,
+        MG = 8,
+        MR = 9,
+        NULL = 10,
+        WKS = 11,
+        PTR = 12,
+        HINFO = 13,
+        MINFO = 14,
+        MX = 15,
+        TXT = 16,
+        RP = 17,
+        AFSDB = 18,
+        SIG = 24,
+        KEY = 25,
+        AAAA = 28,
+        LOC = 29,
+        SRV = 33,
+        NAPTR = 35,
+        KX = 36,
+        CERT = 37,
+        DNAME = 39,
+        OPT = 41,
+        APL = 42,
+        DS = 43,
+        SSHFP = 44,
+        IPSECKEY = 45,
+        RRSIG = 46,
+        NSEC = 47,
+        DNSKEY = 48,
+        DHCID = 49,
+        NSEC3 = 50,
+        NSEC3PARAM = 51,
+        TLSA = 52,
+        SMIMEA = 53,
+        HIP = 55,
+        CDS = 59,
+        CDNSKEY = 60,
+        OPENPGPKEY = 61,
+        CSYNC = 62,
+        ZONEMD = 63,
+        SVCB = 64,
+        HTTPS = 65,
+        SPF = 99,
+        EUI48 = 108,
+        EUI64 = 109,
+        TKEY = 249,
+        TSIG = 250,
+        IXFR = 251,
+        AXFR = 252,
+        MAILB = 253,
+        MAILA = 254,
+        ALL = 255,
+        URI = 256,
+        CAA = 257,
+        AMTRELAY = 260,
+        TA = 32768,
+        DLV = 32769,
     }
 }
 
-#[derive(Debug)]
+code_points! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ResourceClass: u16 as "CLASS" {
+        IN = 1,
+        CS = 2,
+        CH = 3,
+        HS = 4,
+        NONE = 254,
+        ANY = 255,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Answer {
     pub name: DomainName,
     pub resource_type: ResourceType,
     pub class: ResourceClass,
     pub time_to_live: u32,
-    pub length: u16,
-    pub data: Vec<u8>,
+    pub data: RData,
+}
+
+/// Writes the record as a line of a master file.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.name, self.time_to_live, self.class, self.resource_type, self.data
+        )
+    }
 }
 
 impl From<Answer> for Vec<u8> {
     fn from(value: Answer) -> Self {
-        let mut res: Vec<u8> = Vec::new();
-
-        let name = value.name.encode();
-        let resource_type = (value.resource_type as u16).to_be_bytes();
-        let class = (value.class as u16).to_be_bytes();
-        let time_to_live = value.time_to_live.to_be_bytes();
-        let length = value.length.to_be_bytes();
+        let mut buf = Vec::new();
+        let mut encoder = MessageEncoder::new(&mut buf, false, usize::MAX);
 
-        res.extend_from_slice(&name);
-        res.extend_from_slice(&resource_type);
-        res.extend_from_slice(&class);
-        res.extend_from_slice(&time_to_live);
-        res.extend_from_slice(&length);
-        res.extend_from_slice(&value.data);
+        encoder.write_answer(&value);
+        encoder.finish().expect("there is no size limit");
 
-        res
+        buf
     }
 }
 
-#[derive(Debug, Clone)]
+/// A domain name. Labels are arbitrary bytes, written in presentation format with the escapes of
+/// RFC 1035 §5.1. Names compare, hash and order ignoring ASCII case, with ordering following the
+/// canonical order of RFC 4034 §6.1.
+#[derive(Clone)]
 pub struct DomainName {
-    pub labels: Vec<Label>,
-    pub byte_size: u8,
+    labels: Vec<Label>,
+}
+
+#[derive(Clone)]
+pub struct Label(Vec<u8>);
+
+impl Label {
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Result<Self, InvalidName> {
+        let bytes = bytes.into();
+
+        if bytes.is_empty() {
+            return Err(InvalidName::EmptyLabel);
+        }
+
+        if bytes.len() > MAX_LABEL_LENGTH {
+            return Err(InvalidName::LabelTooLong);
+        }
+
+        Ok(Label(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn lowercase_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().map(|byte| byte.to_ascii_lowercase())
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &byte in &self.0 {
+            match byte {
+                b'.' | b'\\' | b'"' | b'(' | b')' | b';' | b'@' | b'$' => {
+                    write!(f, "\\{}", byte as char)?
+                }
+                0x21..=0x7E => write!(f, "{}", byte as char)?,
+                _ => write!(f, "\\{:03}", byte)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Label({})", self)
+    }
+}
+
+impl PartialEq for Label {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct Label {
-    pub name: String,
+impl Eq for Label {}
+
+impl Hash for Label {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+
+        for byte in self.lowercase_bytes() {
+            state.write_u8(byte);
+        }
+    }
+}
+
+impl PartialOrd for Label {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Label {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lowercase_bytes().cmp(other.lowercase_bytes())
+    }
+}
+
+impl PartialEq for DomainName {
+    fn eq(&self, other: &Self) -> bool {
+        self.labels == other.labels
+    }
+}
+
+impl Eq for DomainName {}
+
+impl Hash for DomainName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.labels.hash(state);
+    }
+}
+
+impl PartialOrd for DomainName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DomainName {
+    // Labels are compared starting from the root, and a name sorts before its subdomains
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.labels.iter().rev().cmp(other.labels.iter().rev())
+    }
+}
+
+impl fmt::Display for DomainName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return write!(f, ".");
+        }
+
+        for label in &self.labels {
+            write!(f, "{}.", label)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DomainName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DomainName({})", self)
+    }
+}
+
+/// Reads a name in presentation format. The trailing dot is optional, so every name is taken to
+/// be fully qualified; see [`DomainName::is_absolute`] for resolving relative names.
+impl FromStr for DomainName {
+    type Err = InvalidName;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text == "." {
+            return Ok(DomainName::root());
+        }
+
+        let bytes = text.as_bytes();
+        let mut labels = Vec::new();
+        let mut label = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => {
+                    let digits = bytes
+                        .get(i + 1..i + 4)
+                        .filter(|digits| digits.iter().all(|digit| digit.is_ascii_digit()));
+
+                    match (digits, bytes.get(i + 1)) {
+                        (Some(digits), _) => {
+                            let value = std::str::from_utf8(digits)
+                                .ok()
+                                .and_then(|digits| digits.parse::<u8>().ok())
+                                .ok_or(InvalidName::BadEscape)?;
+
+                            label.push(value);
+                            i += 4;
+                        }
+                        (None, Some(next)) if !next.is_ascii_digit() => {
+                            label.push(*next);
+                            i += 2;
+                        }
+                        _ => return Err(InvalidName::BadEscape),
+                    }
+                }
+                b'.' => {
+                    labels.push(Label::new(std::mem::take(&mut label))?);
+                    i += 1;
+                }
+                byte => {
+                    label.push(byte);
+                    i += 1;
+                }
+            }
+        }
+
+        if !label.is_empty() || labels.is_empty() {
+            labels.push(Label::new(label)?);
+        }
+
+        DomainName::from_labels(labels)
+    }
 }
 
 impl DomainName {
-    pub fn new(value: String) -> Self {
-        let labels: Vec<Label> = value
-            .split(".")
-            .map(|part| Label {
-                name: part.to_string(),
+    pub fn from_labels(labels: Vec<Label>) -> Result<Self, InvalidName> {
+        let name = DomainName { labels };
+
+        if name.encoded_len() > MAX_NAME_LENGTH {
+            return Err(InvalidName::NameTooLong);
+        }
+
+        Ok(name)
+    }
+
+    pub fn root() -> Self {
+        DomainName { labels: vec![] }
+    }
+
+    /// Builds a name from Unicode text such as `bücher.example`, converting each label to its
+    /// ASCII `xn--` form. Labels are lowercased on the way.
+    pub fn from_unicode(text: &str) -> Result<Self, InvalidName> {
+        let text = text.strip_suffix(idna::is_label_separator).unwrap_or(text);
+
+        if text.is_empty() {
+            return Ok(DomainName::root());
+        }
+
+        text.split(idna::is_label_separator)
+            .map(|label| Label::new(idna::to_ascii(label).ok_or(InvalidName::InvalidIdna)?))
+            .collect::<Result<_, _>>()
+            .and_then(DomainName::from_labels)
+    }
+
+    /// Writes the name for people to read, decoding `xn--` labels back to Unicode. Labels that
+    /// aren't valid IDNA are written in presentation format instead.
+    pub fn to_unicode(&self) -> String {
+        if self.is_root() {
+            return ".".to_string();
+        }
+
+        self.labels
+            .iter()
+            .map(|label| {
+                let unicode = std::str::from_utf8(&label.0)
+                    .ok()
+                    .and_then(idna::to_unicode)
+                    .unwrap_or_else(|| label.to_string());
+
+                unicode + "."
             })
-            .collect();
+            .collect()
+    }
+
+    /// Whether presentation format `text` ends in an unescaped dot, making it a fully qualified
+    /// name rather than one relative to an origin.
+    pub fn is_absolute(text: &str) -> bool {
+        let Some(rest) = text.strip_suffix('.') else {
+            return false;
+        };
+
+        let escapes = rest.bytes().rev().take_while(|&byte| byte == b'\\').count();
+
+        escapes % 2 == 0
+    }
+
+    /// Appends `suffix`, as when qualifying a relative name with its origin.
+    pub fn join(&self, suffix: &DomainName) -> Result<DomainName, InvalidName> {
+        DomainName::from_labels(self.labels.iter().chain(&suffix.labels).cloned().collect())
+    }
 
-        let byte_size = labels
+    /// The number of bytes the name takes up on the wire without compression.
+    pub fn encoded_len(&self) -> usize {
+        self.labels
             .iter()
-            .map(|label| label.name.len() + 1)
-            .sum::<usize>() as u8
-            + 1;
+            .map(|label| label.0.len() + 1)
+            .sum::<usize>()
+            + 1
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The labels from the leftmost to the one just below the root.
+    pub fn labels(&self) -> std::slice::Iter<'_, Label> {
+        self.labels.iter()
+    }
+
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// The name with its leftmost label removed, or `None` for the root.
+    pub fn parent(&self) -> Option<DomainName> {
+        (!self.is_root()).then(|| self.suffix(self.labels.len() - 1))
+    }
+
+    /// The name itself followed by each of its parents, ending with the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = DomainName> + '_ {
+        (0..=self.labels.len())
+            .rev()
+            .map(|count| self.suffix(count))
+    }
+
+    /// Whether this name is `other` or lies beneath it.
+    pub fn is_subdomain_of(&self, other: &DomainName) -> bool {
+        self.labels.len() >= other.labels.len()
+            && self.labels[self.labels.len() - other.labels.len()..] == other.labels[..]
+    }
+
+    /// The names a zone cut could sit at between `apex` and this name, nearest to the apex first.
+    /// The apex itself is left out, as is everything when the name isn't inside it.
+    pub fn zone_cut_candidates(&self, apex: &DomainName) -> impl Iterator<Item = DomainName> + '_ {
+        let depth = if self.is_subdomain_of(apex) {
+            apex.labels.len()
+        } else {
+            self.labels.len()
+        };
+
+        (depth + 1..=self.labels.len()).map(|count| self.suffix(count))
+    }
 
-        DomainName { labels, byte_size }
+    /// Whether the leftmost label is the `*` wildcard label (RFC 4592).
+    pub fn is_wildcard(&self) -> bool {
+        self.labels.first().is_some_and(|label| label.0 == b"*")
     }
 
-    pub fn parse(data: &Vec<u8>, original: &Vec<u8>) -> Self {
-        let result = Self::parse_labels_from_buffer(&data, &original);
+    /// Whether `wildcard` (such as `*.example.com`) covers this name, which it does for any name
+    /// strictly beneath `example.com`. This ignores whether closer names exist in the zone, which
+    /// would block the wildcard.
+    pub fn matches_wildcard(&self, wildcard: &DomainName) -> bool {
+        let Some(closest_encloser) = wildcard.is_wildcard().then(|| wildcard.parent()).flatten()
+        else {
+            return false;
+        };
 
+        self.labels.len() > closest_encloser.labels.len() && self.is_subdomain_of(&closest_encloser)
+    }
+
+    // The last `count` labels of the name
+    fn suffix(&self, count: usize) -> DomainName {
         DomainName {
-            labels: result.0,
-            byte_size: result.1,
+            labels: self.labels[self.labels.len() - count..].to_vec(),
         }
     }
 
-    fn parse_labels_from_buffer(data: &Vec<u8>, original: &Vec<u8>) -> (Vec<Label>, u8) {
-        println!("Parsing labels from: {:?}", data);
+    /// Writes the name uncompressed onto the end of `buf`.
+    pub fn encode_into(&self, buf: &mut impl BufMut) {
+        for label in &self.labels {
+            buf.put_u8(label.0.len() as u8);
+            buf.put_slice(&label.0);
+        }
+
+        buf.put_u8(0);
+    }
 
-        let mut parts = Vec::new();
-        let mut byte_size = 0;
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
 
-        let mut offset = 0;
+        self.encode_into(&mut buf);
 
-        loop {
-            let first_byte = data[offset];
-            offset += 1;
+        buf
+    }
+}
 
-            if first_byte & 0b1100_0000 == 0b1100_0000 {
-                // Decode pointer
-                let mut pointer_offset = (first_byte as u16) << 8 | data[offset] as u16;
-                pointer_offset &= !0b1100_0000_0000_0000;
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        collections::{hash_map::DefaultHasher, HashSet},
+        net::{Ipv4Addr, Ipv6Addr},
+    };
 
-                byte_size += 2;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
-                let parse_data = original[pointer_offset as usize..].to_vec();
+    use super::*;
+    use crate::edns::EdnsOption;
+    use crate::message_ref::ParseOptions;
 
-                let labels = Self::parse_labels_from_buffer(&parse_data, original);
+    pub(crate) const CASES: u64 = 2000;
 
-                parts.extend(labels.0);
-                break;
+    // Generates random but well-formed messages. Names are drawn from a small alphabet and reused
+    // across records so that compression has suffixes to share.
+    pub(crate) struct Generator {
+        rng: StdRng,
+        names: Vec<DomainName>,
+    }
+
+    impl Generator {
+        pub(crate) fn new(seed: u64) -> Self {
+            Generator {
+                rng: StdRng::seed_from_u64(seed),
+                names: vec![],
+            }
+        }
+
+        fn label(&mut self) -> Label {
+            let length = if self.rng.gen_ratio(1, 20) {
+                self.rng.gen_range(1..=MAX_LABEL_LENGTH)
             } else {
-                // Decode string
-                if first_byte == 0 {
-                    byte_size += 1;
-                    break;
+                self.rng.gen_range(1..=4)
+            };
+
+            let bytes: Vec<u8> = (0..length)
+                .map(|_| {
+                    if self.rng.gen_ratio(1, 10) {
+                        self.rng.gen()
+                    } else {
+                        b"abcAB-"[self.rng.gen_range(0..6)]
+                    }
+                })
+                .collect();
+
+            Label::new(bytes).unwrap()
+        }
+
+        fn name(&mut self) -> DomainName {
+            let name = match self.rng.gen_range(0..4) {
+                0 if !self.names.is_empty() => {
+                    self.names[self.rng.gen_range(0..self.names.len())].clone()
                 }
+                1 if !self.names.is_empty() => {
+                    let parent = self.names[self.rng.gen_range(0..self.names.len())].clone();
+                    let mut labels = vec![self.label()];
+                    labels.extend(parent.labels().cloned());
 
-                let len = first_byte;
+                    DomainName::from_labels(labels).unwrap_or(parent)
+                }
+                _ => {
+                    let mut labels = Vec::new();
 
-                byte_size += len + 1;
+                    for _ in 0..self.rng.gen_range(0..5) {
+                        labels.push(self.label());
 
-                let parse_data = data[offset..offset + len as usize].to_vec();
-                offset += len as usize;
+                        if DomainName::from_labels(labels.clone()).is_err() {
+                            labels.pop();
+                            break;
+                        }
+                    }
 
-                parts.push(Label {
-                    name: String::from_utf8(parse_data).expect("Unable to decode label"),
-                });
+                    DomainName::from_labels(labels).unwrap()
+                }
+            };
+
+            self.names.push(name.clone());
+
+            name
+        }
+
+        fn bytes(&mut self, max: usize) -> Vec<u8> {
+            (0..self.rng.gen_range(0..=max))
+                .map(|_| self.rng.gen())
+                .collect()
+        }
+
+        fn rdata(&mut self) -> (ResourceType, RData) {
+            match self.rng.gen_range(0..11) {
+                0 => (
+                    ResourceType::A,
+                    RData::A(Ipv4Addr::from(self.rng.gen::<u32>())),
+                ),
+                1 => (
+                    ResourceType::AAAA,
+                    RData::AAAA(Ipv6Addr::from(self.rng.gen::<u128>())),
+                ),
+                2 => (ResourceType::CNAME, RData::CNAME(self.name())),
+                3 => (ResourceType::NS, RData::NS(self.name())),
+                4 => (ResourceType::PTR, RData::PTR(self.name())),
+                5 => (
+                    ResourceType::MX,
+                    RData::MX {
+                        preference: self.rng.gen(),
+                        exchange: self.name(),
+                    },
+                ),
+                6 => {
+                    let strings = (0..self.rng.gen_range(0..4))
+                        .map(|_| self.bytes(255))
+                        .collect();
+
+                    (ResourceType::TXT, RData::TXT(strings))
+                }
+                7 => (
+                    ResourceType::SOA,
+                    RData::SOA {
+                        mname: self.name(),
+                        rname: self.name(),
+                        serial: self.rng.gen(),
+                        refresh: self.rng.gen(),
+                        retry: self.rng.gen(),
+                        expire: self.rng.gen(),
+                        minimum: self.rng.gen(),
+                    },
+                ),
+                8 => (
+                    ResourceType::SRV,
+                    RData::SRV {
+                        priority: self.rng.gen(),
+                        weight: self.rng.gen(),
+                        port: self.rng.gen(),
+                        target: self.name(),
+                    },
+                ),
+                9 => {
+                    let tag = (0..self.rng.gen_range(0..16))
+                        .map(|_| self.rng.gen_range(b'a'..=b'z') as char)
+                        .collect();
+
+                    (
+                        ResourceType::CAA,
+                        RData::CAA {
+                            flags: self.rng.gen(),
+                            tag,
+                            value: self.bytes(32),
+                        },
+                    )
+                }
+                _ => {
+                    // Any type the decoder has no structure for comes back as opaque bytes
+                    let resource_type = loop {
+                        let resource_type = ResourceType::from(self.rng.gen::<u16>());
+
+                        if RData::parse(&[], 0, 0, resource_type)
+                            .is_ok_and(|data| matches!(data, RData::Unknown(..)))
+                            && resource_type != ResourceType::OPT
+                        {
+                            break resource_type;
+                        }
+                    };
+
+                    (resource_type, RData::Unknown(resource_type, self.bytes(64)))
+                }
+            }
+        }
+
+        fn record(&mut self) -> Answer {
+            let name = self.name();
+            let (resource_type, data) = self.rdata();
+
+            Answer {
+                name,
+                resource_type,
+                class: ResourceClass::from(self.rng.gen_range(1..=4)),
+                time_to_live: self.rng.gen(),
+                data,
+            }
+        }
+
+        fn records(&mut self) -> Vec<Answer> {
+            (0..self.rng.gen_range(0..4))
+                .map(|_| self.record())
+                .collect()
+        }
+
+        pub(crate) fn message(&mut self) -> Message {
+            let edns = self.rng.gen_bool(0.5).then(|| Edns {
+                udp_payload_size: self.rng.gen(),
+                version: self.rng.gen(),
+                dnssec_ok: self.rng.gen(),
+                options: (0..self.rng.gen_range(0..3))
+                    .map(|_| EdnsOption {
+                        code: self.rng.gen(),
+                        data: self.bytes(16),
+                    })
+                    .collect(),
+            });
+
+            // Without an OPT record there is nowhere to put the upper bits of the response code
+            let response_code = if edns.is_some() {
+                self.rng.gen_range(0..4096)
+            } else {
+                self.rng.gen_range(0..16)
             };
+
+            let questions: Vec<Question> = (0..self.rng.gen_range(0..3))
+                .map(|_| Question {
+                    name: self.name(),
+                    question_type: ResourceType::from(self.rng.gen::<u16>()),
+                    question_class: ResourceClass::from(self.rng.gen::<u16>()),
+                })
+                .collect();
+
+            let mut message = Message::builder()
+                .id(self.rng.gen())
+                .response(self.rng.gen())
+                .op_code(OperationCode::from(self.rng.gen_range(0..16)))
+                .authoritative(self.rng.gen())
+                .truncated(self.rng.gen())
+                .recursion_desired(self.rng.gen())
+                .recursion_available(self.rng.gen())
+                .authentic_data(self.rng.gen())
+                .checking_disabled(self.rng.gen())
+                .response_code(ResponseCode::from(response_code))
+                .questions(questions)
+                .answers(self.records())
+                .authorities(self.records())
+                .additionals(self.records())
+                .edns(edns)
+                .build();
+
+            message.header.zero = self.rng.gen();
+
+            message
         }
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        for seed in 0..CASES {
+            let message = Generator::new(seed).message();
+
+            for compression in [false, true] {
+                let encoded = message.encode(compression);
+                let decoded = Message::try_from(encoded.as_slice());
+
+                assert_eq!(
+                    decoded.as_ref(),
+                    Ok(&message),
+                    "seed {} with compression {}",
+                    seed,
+                    compression
+                );
+            }
+        }
+    }
 
-        (parts, byte_size)
+    #[test]
+    fn headers_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..CASES {
+            let bytes: [u8; 12] = rng.gen();
+            let encoded: [u8; 12] = Header::from(bytes).into();
+
+            assert_eq!(encoded, bytes);
+        }
     }
 
-    pub fn encode(self) -> Vec<u8> {
-        let mut res: Vec<u8> = Vec::new();
+    #[test]
+    fn names_round_trip_through_presentation_format() {
+        let mut generator = Generator::new(0);
+
+        for _ in 0..CASES {
+            let name = generator.name();
+            let parsed: DomainName = name.to_string().parse().unwrap();
 
-        for label in self.labels {
-            res.push(label.name.len() as u8);
-            res.extend(label.name.as_bytes());
+            assert_eq!(parsed.encode(), name.encode(), "{}", name);
         }
+    }
 
-        res.push(0);
+    fn names(names: &[&str]) -> Vec<DomainName> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
 
-        res
+    #[test]
+    fn names_compare_and_hash_without_case() {
+        let hash = |name: &DomainName| {
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        for (a, b) in [
+            ("example.com.", "EXAMPLE.com."),
+            ("wWw.Example.COM.", "www.example.com."),
+            ("\\200.example.", "\\200.EXAMPLE."),
+            (".", "."),
+        ] {
+            let (a, b): (DomainName, DomainName) = (a.parse().unwrap(), b.parse().unwrap());
+
+            assert_eq!(a, b);
+            assert_eq!(a.cmp(&b), Ordering::Equal);
+            assert_eq!(hash(&a), hash(&b), "{}", a);
+        }
+
+        let set: HashSet<DomainName> = names(&["a.example.", "A.EXAMPLE.", "a.Example."])
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 1);
+
+        // Only ASCII letters are folded
+        assert_ne!(
+            "\\195\\164.example.".parse::<DomainName>().unwrap(),
+            "\\195\\132.example.".parse::<DomainName>().unwrap()
+        );
+    }
+
+    // The example from RFC 4034 §6.1
+    #[test]
+    fn names_sort_in_canonical_order() {
+        let sorted = names(&[
+            "example.",
+            "a.example.",
+            "yljkjljk.a.example.",
+            "Z.a.example.",
+            "zABC.a.EXAMPLE.",
+            "z.example.",
+            "\\001.z.example.",
+            "*.z.example.",
+            "\\200.z.example.",
+        ]);
+
+        let mut shuffled = sorted.clone();
+        shuffled.reverse();
+        shuffled.swap(1, 6);
+        shuffled.sort();
+
+        assert_eq!(shuffled, sorted);
+        assert!(DomainName::root() < sorted[0]);
+    }
+
+    #[test]
+    fn names_break_down_into_ancestors_and_cuts() {
+        let name: DomainName = "a.b.c.Example.".parse().unwrap();
+        let apex: DomainName = "example.".parse().unwrap();
+
+        assert_eq!(DomainName::root().label_count(), 0);
+        assert_eq!(apex.label_count(), 1);
+        assert_eq!(name.label_count(), 4);
+
+        assert_eq!(
+            name.ancestors().collect::<Vec<_>>(),
+            names(&[
+                "a.b.c.example.",
+                "b.c.example.",
+                "c.example.",
+                "example.",
+                "."
+            ])
+        );
+
+        assert_eq!(
+            name.zone_cut_candidates(&apex).collect::<Vec<_>>(),
+            names(&["c.example.", "b.c.example.", "a.b.c.example."])
+        );
+        assert_eq!(apex.zone_cut_candidates(&apex).count(), 0);
+        assert_eq!(
+            name.zone_cut_candidates(&"example.net.".parse().unwrap())
+                .count(),
+            0
+        );
+        assert_eq!(
+            name.zone_cut_candidates(&DomainName::root()).count(),
+            name.label_count()
+        );
+    }
+
+    #[test]
+    fn wildcards_cover_names_strictly_beneath_them() {
+        let wildcard: DomainName = "*.Example.".parse().unwrap();
+
+        for (name, matches) in [
+            ("a.example.", true),
+            ("b.A.EXAMPLE.", true),
+            ("*.example.", true),
+            ("example.", false),
+            ("a.example.net.", false),
+            ("a.xexample.", false),
+            (".", false),
+        ] {
+            let name: DomainName = name.parse().unwrap();
+
+            assert_eq!(name.matches_wildcard(&wildcard), matches, "{}", name);
+        }
+
+        // Only a name whose first label is exactly `*` is a wildcard
+        let a: DomainName = "a.example.".parse().unwrap();
+        for other in ["example.", "b.example.", "\\*x.example."] {
+            assert!(!a.matches_wildcard(&other.parse().unwrap()), "{}", other);
+        }
+        assert!(!a.matches_wildcard(&DomainName::root()));
+        assert!("a."
+            .parse::<DomainName>()
+            .unwrap()
+            .matches_wildcard(&"*.".parse().unwrap()));
+    }
+
+    #[test]
+    fn encoding_stops_at_max_size() {
+        let mut buf = BytesMut::new();
+
+        for seed in 0..CASES {
+            let message = Generator::new(seed).message();
+            let encoded = message.encode(true);
+
+            buf.clear();
+            assert_eq!(
+                message.encode_into(&mut buf, true, encoded.len()),
+                Ok(encoded.len())
+            );
+            assert_eq!(buf, encoded);
+
+            buf.clear();
+            let max_size = encoded.len() - 1;
+            assert_eq!(
+                message.encode_into(&mut buf, true, max_size),
+                Err(EncodeError::TooLong { max_size })
+            );
+            assert!(encoded.starts_with(&buf));
+        }
+    }
+
+    #[test]
+    fn truncation_drops_whole_rrsets_from_the_end() {
+        let ns = |target: &str| Answer {
+            resource_type: ResourceType::NS,
+            data: RData::NS(target.parse().unwrap()),
+            ..a_record("example.com.", Ipv4Addr::LOCALHOST)
+        };
+
+        let mut message = query("www.example.com.");
+        message.answers = vec![
+            a_record("www.example.com.", Ipv4Addr::new(192, 0, 2, 1)),
+            a_record("www.example.com.", Ipv4Addr::new(192, 0, 2, 2)),
+        ];
+        message.authorities = vec![ns("ns1.example.com."), ns("ns2.example.com.")];
+        message.additionals = vec![
+            a_record("ns1.example.com.", Ipv4Addr::new(192, 0, 2, 53)),
+            a_record("ns1.example.com.", Ipv4Addr::new(192, 0, 2, 54)),
+            a_record("ns2.example.com.", Ipv4Addr::new(192, 0, 2, 55)),
+        ];
+        message.edns = Some(Edns::new(1232));
+
+        // How many answer, authority and additional records are left after dropping each RRset
+        let kept = [(2, 2, 3), (2, 2, 2), (2, 2, 0), (2, 0, 0), (0, 0, 0)];
+
+        let with = |(answers, authorities, additionals): (usize, usize, usize)| Message {
+            answers: message.answers[..answers].to_vec(),
+            authorities: message.authorities[..authorities].to_vec(),
+            additionals: message.additionals[..additionals].to_vec(),
+            ..message.clone()
+        };
+
+        let full = message.encode(true).len();
+        let empty = with((0, 0, 0)).encode(true).len();
+
+        for max_size in empty..=full {
+            let mut truncated = message.clone();
+            truncated.truncate(max_size, true);
+
+            // As few RRsets as possible are dropped to make it fit
+            let expected = *kept
+                .iter()
+                .find(|&&counts| with(counts).encode(true).len() <= max_size)
+                .unwrap();
+
+            assert!(truncated.encode(true).len() <= max_size, "{}", max_size);
+            assert_eq!(truncated.edns, message.edns, "{}", max_size);
+
+            // Only losing answer or authority records sets TC
+            let mut expected = with(expected);
+            expected.header.truncation =
+                expected.answers.len() < 2 || expected.authorities.len() < 2;
+
+            assert_eq!(truncated, expected, "{}", max_size);
+        }
+
+        // Even with everything dropped the question and OPT record don't fit
+        let mut truncated = message.clone();
+        truncated.truncate(empty - 1, true);
+
+        assert!(truncated.header.truncation);
+        assert!(truncated.answers.is_empty() && truncated.additionals.is_empty());
+        assert_eq!(truncated.questions, message.questions);
+        assert_eq!(truncated.edns, message.edns);
+    }
+
+    #[test]
+    fn oversized_fields_are_not_encoded() {
+        let mut message = query("example.com.");
+        message.answers.push(Answer {
+            data: RData::TXT(vec![vec![b'x'; 255]; 300]),
+            ..a_record("example.com.", Ipv4Addr::LOCALHOST)
+        });
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::RDataTooLong { length: 76800 })
+        );
+
+        // Nothing can be dropped to fix it, so truncating leaves it alone
+        message.truncate(512, true);
+        assert_eq!(message.answers.len(), 1);
+
+        let mut message = query("example.com.");
+        message.answers.push(Answer {
+            data: RData::TXT(vec![b"short".to_vec(), vec![b'x'; 256]]),
+            ..a_record("example.com.", Ipv4Addr::LOCALHOST)
+        });
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::CharacterStringTooLong { length: 256 })
+        );
+
+        message.answers[0].data = RData::CAA {
+            flags: 0,
+            tag: "x".repeat(300),
+            value: b"ca.example".to_vec(),
+        };
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::CharacterStringTooLong { length: 300 })
+        );
+
+        let mut message = query("example.com.");
+        let mut edns = Edns::new(1232);
+        edns.options.push(EdnsOption {
+            code: 10,
+            data: vec![0; 65536],
+        });
+        message.edns = Some(edns);
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::OptionTooLong { length: 65536 })
+        );
+
+        let mut message = query("example.com.");
+        message.additionals = vec![a_record("example.com.", Ipv4Addr::LOCALHOST); 65536];
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::TooManyEntries { count: 65536 })
+        );
+
+        message.edns = Some(Edns::new(1232));
+        message.additionals.pop();
+
+        assert_eq!(
+            message.encode_into(&mut Vec::new(), true, usize::MAX),
+            Err(EncodeError::TooManyEntries { count: 65536 })
+        );
+    }
+
+    // Damages valid messages the way a hostile peer might, to check that decoding reports an error
+    // instead of panicking.
+    #[test]
+    fn mutated_messages_do_not_panic() {
+        for seed in 0..CASES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut encoded = Generator::new(seed).message().encode(rng.gen());
+
+            for _ in 0..rng.gen_range(1..8) {
+                let index = rng.gen_range(0..encoded.len());
+
+                match rng.gen_range(0..4) {
+                    0 => encoded[index] = rng.gen(),
+                    1 => encoded[index] ^= 0b1100_0000,
+                    2 => encoded.truncate(index.max(1)),
+                    _ => encoded.insert(index, rng.gen()),
+                }
+            }
+
+            let _ = Message::try_from(encoded.as_slice());
+        }
+    }
+
+    fn query(name: &str) -> Message {
+        Message::builder()
+            .id(1)
+            .question(Question {
+                name: name.parse().unwrap(),
+                question_type: ResourceType::A,
+                question_class: ResourceClass::IN,
+            })
+            .build()
+    }
+
+    fn a_record(name: &str, address: Ipv4Addr) -> Answer {
+        Answer {
+            name: name.parse().unwrap(),
+            resource_type: ResourceType::A,
+            class: ResourceClass::IN,
+            time_to_live: 60,
+            data: RData::A(address),
+        }
+    }
+
+    #[test]
+    fn strict_parsing_rejects_what_default_parsing_accepts() {
+        let mut reserved_bit = query("example.com.");
+        reserved_bit.header.zero = true;
+
+        let two_questions = Message::builder()
+            .questions(query("a.example.").questions)
+            .questions(query("b.example.").questions)
+            .build();
+
+        for (message, error) in [
+            (reserved_bit, WireError::ReservedBitSet),
+            (
+                Message::builder().build(),
+                WireError::QuestionCount { count: 0 },
+            ),
+            (two_questions, WireError::QuestionCount { count: 2 }),
+        ] {
+            let encoded = message.encode(true);
+
+            assert_eq!(Message::try_from(encoded.as_slice()), Ok(message));
+            assert_eq!(
+                Message::parse(&encoded, ParseOptions::strict()).map(|parsed| parsed.message),
+                Err(error)
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_parsing_keeps_what_it_can() {
+        let message = Message::builder()
+            .id(1)
+            .response(true)
+            .questions(query("example.com.").questions)
+            .answers([
+                a_record("a.example.com.", Ipv4Addr::new(192, 0, 2, 1)),
+                a_record("b.example.com.", Ipv4Addr::new(192, 0, 2, 2)),
+                a_record("c.example.com.", Ipv4Addr::new(192, 0, 2, 3)),
+            ])
+            .build();
+        let mut encoded = message.encode(false);
+
+        // Give the middle record an extra byte of RDATA, then add trailing junk
+        let second = encoded
+            .windows(4)
+            .position(|window| window == [192, 0, 2, 2])
+            .unwrap();
+        encoded[second - 1] = 5;
+        encoded.insert(second + 4, 0);
+        encoded.extend_from_slice(&[0xde, 0xad]);
+
+        assert!(Message::parse(&encoded, ParseOptions::strict()).is_err());
+
+        let parsed = Message::parse(&encoded, ParseOptions::lenient()).unwrap();
+        let addresses: Vec<&RData> = parsed
+            .message
+            .answers
+            .iter()
+            .map(|answer| &answer.data)
+            .collect();
+
+        assert_eq!(
+            addresses,
+            [
+                &RData::A(Ipv4Addr::new(192, 0, 2, 1)),
+                &RData::A(Ipv4Addr::new(192, 0, 2, 3)),
+            ]
+        );
+        assert_eq!(parsed.message.header.answer_count, 2);
+        assert!(matches!(
+            parsed.warnings.as_slice(),
+            [
+                WireError::RDataLengthMismatch { .. },
+                WireError::TrailingBytes { remaining: 2, .. },
+            ]
+        ));
+
+        // Cut off in the middle of the last record, the records before it are still kept
+        let cut = message.encode(false);
+        let parsed = Message::parse(&cut[..cut.len() - 2], ParseOptions::lenient()).unwrap();
+
+        assert_eq!(parsed.message.answers, message.answers[..2]);
+        assert!(matches!(
+            parsed.warnings.as_slice(),
+            [WireError::UnexpectedEnd { .. }]
+        ));
+    }
+
+    #[test]
+    fn lenient_parsing_agrees_with_strict_parsing() {
+        for seed in 0..CASES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut encoded = Generator::new(seed).message().encode(rng.gen());
+
+            if rng.gen() {
+                let index = rng.gen_range(0..encoded.len());
+                encoded[index] = rng.gen();
+            }
+
+            // Only a message too short for a header can't be salvaged at all
+            let lenient = Message::parse(&encoded, ParseOptions::lenient()).unwrap();
+
+            if let Ok(strict) = Message::parse(&encoded, ParseOptions::strict()) {
+                assert!(strict.warnings.is_empty());
+                assert!(lenient.warnings.is_empty());
+                assert_eq!(lenient.message, strict.message);
+            }
+        }
+    }
+
+    // A name reached through a pointer to another name that itself ends in a pointer. Working out
+    // where the first name ended used to underflow on the second hop.
+    #[test]
+    fn chained_pointers_decode() {
+        let message = [
+            0x00, 0x00, 0x01, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // header
+            0x01, b'a', 0x00, 0x00, 0x01, 0x00, 0x01, // a.
+            0x01, b'b', 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, // b. then a pointer to a.
+            0xc0, 0x13, 0x00, 0x01, 0x00, 0x01, // a pointer to b.a.
+        ];
+
+        let message = Message::try_from(message.as_slice()).unwrap();
+        let names: Vec<String> = message
+            .questions
+            .iter()
+            .map(|question| question.name.to_string())
+            .collect();
+
+        assert_eq!(names, ["a.", "b.a.", "b.a."]);
+    }
+
+    // A header with the given section counts followed by `body`, which starts at offset 12
+    fn packet(questions: u16, answers: u16, body: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x00, 0x00, 0x01, 0x00];
+        packet.extend_from_slice(&questions.to_be_bytes());
+        packet.extend_from_slice(&answers.to_be_bytes());
+        packet.extend_from_slice(&[0x00; 4]);
+        packet.extend_from_slice(body);
+        packet
+    }
+
+    #[test]
+    fn bad_pointers_are_reported() {
+        let question = [0x00, 0x01, 0x00, 0x01];
+
+        for (body, error) in [
+            (
+                vec![0xc0, 0x0c],
+                WireError::ForwardPointer {
+                    offset: 12,
+                    target: 12,
+                },
+            ),
+            (
+                vec![0xc0, 0x0e, 0x00, 0x00, 0x01, 0x00, 0x01],
+                WireError::ForwardPointer {
+                    offset: 12,
+                    target: 14,
+                },
+            ),
+            (
+                vec![0x01, b'a', 0xc0, 0x05],
+                WireError::PointerIntoHeader {
+                    offset: 14,
+                    target: 5,
+                },
+            ),
+            (
+                vec![0xc0, 0xff],
+                WireError::PointerOutOfBounds {
+                    offset: 12,
+                    target: 255,
+                },
+            ),
+        ] {
+            let mut body = body;
+            body.extend_from_slice(&question);
+
+            assert_eq!(
+                Message::try_from(packet(1, 0, &body).as_slice()),
+                Err(error),
+                "{:02x?}",
+                body
+            );
+        }
+
+        // Four names of 64 bytes each, all but the first ending in a pointer to the one before,
+        // so only the last is too long once the pointers are followed
+        let mut body = Vec::new();
+        let mut previous = None;
+
+        for _ in 0..4 {
+            let offset = 12 + body.len();
+
+            body.push(63);
+            body.extend_from_slice(&[b'a'; 63]);
+
+            match previous {
+                Some(previous) => body.extend_from_slice(&(0xc000 | previous as u16).to_be_bytes()),
+                None => body.push(0),
+            }
+
+            body.extend_from_slice(&question);
+            previous = Some(offset);
+        }
+
+        assert_eq!(
+            Message::try_from(packet(4, 0, &body).as_slice()),
+            Err(WireError::NameTooLong {
+                offset: previous.unwrap()
+            })
+        );
+
+        // Every pointer goes backwards, but a chain of them can still be longer than is allowed.
+        // The first record's RDATA is a chain of pointers back to the root name of the question,
+        // and the second record's name points at its end.
+        // The root name and question take 5 bytes, then the record's fields up to its RDATA 11
+        let start = 12 + 5 + 11;
+        let mut rdata = vec![0xc0, 0x0c];
+        for i in 1..MAX_POINTER_DEPTH {
+            rdata.extend_from_slice(&(0xc000 | (start + 2 * (i - 1)) as u16).to_be_bytes());
+        }
+
+        let last = start + rdata.len() - 2;
+
+        let mut body = vec![0x00];
+        body.extend_from_slice(&question);
+        body.extend_from_slice(&[0x00, 0x00, 0x63, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]);
+        body.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        body.extend_from_slice(&rdata);
+
+        let second = 12 + body.len();
+        body.extend_from_slice(&(0xc000 | last as u16).to_be_bytes());
+        body.extend_from_slice(&[0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            Message::try_from(packet(1, 2, &body).as_slice()),
+            Err(WireError::PointerLoop { offset: second })
+        );
+    }
+
+    // Inputs the decode_message fuzz target found to panic
+    #[test]
+    fn fuzz_regressions() {
+        let inputs: &[&[u8]] = &[&[
+            0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xc0, 0x0c, 0x00, 0x00, 0x00,
+            0xc0, 0xc0, 0x16, 0x00, 0x00, 0xc0, 0xc0, 0xc0, 0xc0, 0x0c, 0x00, 0x00, 0x00, 0xc0,
+            0xc0, 0x16,
+        ]];
+
+        for input in inputs {
+            assert!(Message::try_from(*input).is_err());
+        }
     }
 }
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::edns::{Edns, MAX_UDP_PAYLOAD};
+use crate::error::UpstreamError;
+use crate::message_ref::{MessageRef, ParseOptions, QuestionRef};
+use crate::types::{Message, OperationCode, Question};
+
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct PendingQuery {
+    question: Question,
+    sender: Sender<Message>,
+}
+
+/// A single UDP socket to the upstream resolver shared by every worker. Responses are read on a
+/// dedicated thread and handed back to whichever worker sent the matching query, so many queries
+/// can be in flight at once.
+pub struct Upstream {
+    addr: SocketAddr,
+    socket: UdpSocket,
+    pending: Arc<Mutex<HashMap<u16, PendingQuery>>>,
+}
+
+impl Upstream {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind("127.0.0.1:2054")?;
+        socket.connect(addr)?;
+
+        let addr = socket.peer_addr()?;
+
+        let pending: Arc<Mutex<HashMap<u16, PendingQuery>>> = Arc::default();
+
+        let reader = socket.try_clone()?;
+        let reader_pending = Arc::clone(&pending);
+
+        thread::spawn(move || {
+            let mut buf = [0; MAX_UDP_PAYLOAD as usize];
+
+            loop {
+                let size = match reader.recv(&mut buf) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        eprintln!("Error receiving from resolver: {}", e);
+                        continue;
+                    }
+                };
+
+                let response = match MessageRef::parse(&buf[..size]) {
+                    Ok(response) => response,
+                    Err(e) => {
+                        eprintln!("Unable to parse resolver response: {}", e);
+                        continue;
+                    }
+                };
+
+                let id = response.header().id;
+                let mut pending = reader_pending.lock().unwrap();
+
+                // Only accept a response that answers the question we asked under that ID, so a
+                // stray or spoofed packet can't be handed to the wrong client. This is checked
+                // before decoding the rest of the response so junk is dropped cheaply.
+                let matches = pending.get(&id).is_some_and(|query| {
+                    response
+                        .questions()
+                        .next()
+                        .and_then(|question| question.and_then(QuestionRef::to_question).ok())
+                        .is_some_and(|question| same_question(&question, &query.question))
+                });
+
+                if !matches {
+                    eprintln!("Discarding unexpected resolver response with id {}", id);
+                    continue;
+                }
+
+                match response.to_message_with(ParseOptions::lenient()) {
+                    Ok(response) => {
+                        for warning in &response.warnings {
+                            eprintln!("Problem with resolver response: {}", warning);
+                        }
+
+                        let query = pending.remove(&id).unwrap();
+                        let _ = query.sender.send(response.message);
+                    }
+                    Err(e) => eprintln!("Unable to parse resolver response: {}", e),
+                }
+            }
+        });
+
+        Ok(Upstream {
+            addr,
+            socket,
+            pending,
+        })
+    }
+
+    pub fn query(
+        &self,
+        question: &Question,
+        op_code: OperationCode,
+    ) -> Result<Message, UpstreamError> {
+        let (sender, receiver) = mpsc::channel();
+
+        let id = {
+            let mut pending = self.pending.lock().unwrap();
+
+            let mut id: u16 = rand::random();
+            while pending.contains_key(&id) {
+                id = rand::random();
+            }
+
+            pending.insert(
+                id,
+                PendingQuery {
+                    question: question.clone(),
+                    sender,
+                },
+            );
+
+            id
+        };
+
+        let message = Message::builder()
+            .id(id)
+            .op_code(op_code)
+            .question(question.clone())
+            .edns(Some(Edns::new(MAX_UDP_PAYLOAD)))
+            .build();
+
+        let message: Vec<u8> = message.into();
+
+        let result = match self.socket.send(&message) {
+            Ok(_) => receiver
+                .recv_timeout(UPSTREAM_TIMEOUT)
+                .map_err(|_| UpstreamError::Timeout),
+            Err(e) => Err(UpstreamError::Io(e)),
+        };
+
+        if result.is_err() {
+            self.pending.lock().unwrap().remove(&id);
+        }
+
+        match result {
+            // The answer didn't fit in a datagram, so ask again over TCP to get all of it
+            Ok(response) if response.header.truncation => {
+                println!("Resolver response was truncated, retrying over TCP");
+
+                self.query_tcp(&message, id, question)
+            }
+            result => result,
+        }
+    }
+
+    fn query_tcp(
+        &self,
+        message: &[u8],
+        id: u16,
+        question: &Question,
+    ) -> Result<Message, UpstreamError> {
+        let mut stream = TcpStream::connect_timeout(&self.addr, UPSTREAM_TIMEOUT)?;
+        stream.set_read_timeout(Some(UPSTREAM_TIMEOUT))?;
+        stream.set_write_timeout(Some(UPSTREAM_TIMEOUT))?;
+
+        let mut framed = Vec::with_capacity(message.len() + 2);
+        framed.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        framed.extend_from_slice(message);
+        stream.write_all(&framed)?;
+
+        let mut length = [0; 2];
+        stream.read_exact(&mut length)?;
+
+        let mut buf = vec![0; u16::from_be_bytes(length) as usize];
+        stream.read_exact(&mut buf)?;
+
+        let response = Message::parse(&buf, ParseOptions::lenient())?;
+
+        for warning in &response.warnings {
+            eprintln!("Problem with resolver response: {}", warning);
+        }
+
+        let response = response.message;
+
+        let matches = response.header.id == id
+            && response
+                .questions
+                .first()
+                .is_some_and(|answered| same_question(answered, question));
+
+        if !matches {
+            return Err(UpstreamError::Mismatch);
+        }
+
+        Ok(response)
+    }
+}
+
+fn same_question(a: &Question, b: &Question) -> bool {
+    a.name == b.name && a.question_type == b.question_type && a.question_class == b.question_class
+}
@@ -0,0 +1,637 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::ZoneError;
+use crate::master::{parse_name, parse_ttl, tokenize, RecordParser};
+use crate::rdata::RData;
+use crate::types::{Answer, DomainName, Question, ResourceClass, ResourceType, ResponseCode};
+
+const MAX_INCLUDE_DEPTH: usize = 8;
+const MAX_CNAME_CHAIN: usize = 8;
+
+#[derive(Debug, Default)]
+pub struct ZoneStore {
+    zones: HashMap<DomainName, Zone>,
+}
+
+impl ZoneStore {
+    pub fn new() -> Self {
+        ZoneStore::default()
+    }
+
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), ZoneError> {
+        self.insert(Zone::load(path)?);
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, zone: Zone) {
+        self.zones.insert(zone.origin.clone(), zone);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    /// Finds the most specific zone containing `name` that serves `class`.
+    pub fn find(&self, name: &DomainName, class: ResourceClass) -> Option<&Zone> {
+        name.ancestors()
+            .filter_map(|ancestor| self.zones.get(&ancestor))
+            .find(|zone| zone.serves(class))
+    }
+}
+
+#[derive(Debug)]
+pub struct Zone {
+    pub origin: DomainName,
+    soa: Answer,
+    records: HashMap<DomainName, Vec<Answer>>,
+}
+
+#[derive(Debug)]
+pub struct ZoneAnswer {
+    pub authoritative: bool,
+    pub response_code: ResponseCode,
+    pub answers: Vec<Answer>,
+    pub authorities: Vec<Answer>,
+    pub additionals: Vec<Answer>,
+}
+
+impl Zone {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ZoneError> {
+        let path = path.as_ref();
+
+        let mut parser = ZoneParser::default();
+        parser.parse_file(path, 0)?;
+
+        let soa = parser
+            .records
+            .iter()
+            .find(|record| record.resource_type == ResourceType::SOA)
+            .cloned()
+            .ok_or_else(|| ZoneError::MissingSoa {
+                path: path.to_path_buf(),
+            })?;
+
+        let mut records: HashMap<DomainName, Vec<Answer>> = HashMap::new();
+
+        for record in parser.records {
+            records.entry(record.name.clone()).or_default().push(record);
+        }
+
+        Ok(Zone {
+            origin: soa.name.clone(),
+            soa,
+            records,
+        })
+    }
+
+    /// Whether questions in `class` can be answered from this zone. Every record in a zone shares
+    /// the class of its SOA.
+    pub fn serves(&self, class: ResourceClass) -> bool {
+        class == self.soa.class || class == ResourceClass::ANY
+    }
+
+    pub fn lookup(&self, question: &Question) -> ZoneAnswer {
+        if !self.serves(question.question_class) {
+            return ZoneAnswer {
+                authoritative: false,
+                response_code: ResponseCode::Refused,
+                answers: vec![],
+                authorities: vec![],
+                additionals: vec![],
+            };
+        }
+
+        let mut answers = Vec::new();
+        let mut name = question.name.clone();
+
+        for _ in 0..MAX_CNAME_CHAIN {
+            if let Some(referral) = self.find_delegation(&name) {
+                return self.referral(answers, referral);
+            }
+
+            let Some(records) = self.records.get(&name) else {
+                // A name that only exists because something lives beneath it is an empty
+                // non-terminal, which gets a NODATA rather than an NXDOMAIN response
+                let exists = self
+                    .records
+                    .keys()
+                    .any(|owner| owner.is_subdomain_of(&name));
+
+                return self.negative_answer(answers, exists);
+            };
+
+            let matching: Vec<Answer> = records
+                .iter()
+                .filter(|record| {
+                    question.question_type == ResourceType::ALL
+                        || record.resource_type == question.question_type
+                })
+                .cloned()
+                .collect();
+
+            if !matching.is_empty() {
+                answers.extend(matching);
+
+                return self.positive_answer(answers);
+            }
+
+            let cname = records
+                .iter()
+                .find(|record| record.resource_type == ResourceType::CNAME);
+
+            match cname {
+                Some(cname) if question.question_type != ResourceType::CNAME => {
+                    answers.push(cname.clone());
+
+                    let RData::CNAME(target) = &cname.data else {
+                        return self.positive_answer(answers);
+                    };
+
+                    // Only chase the alias while it stays inside this zone
+                    if !target.is_subdomain_of(&self.origin) {
+                        return self.positive_answer(answers);
+                    }
+
+                    name = target.clone();
+                }
+                _ => return self.negative_answer(answers, true),
+            }
+        }
+
+        self.positive_answer(answers)
+    }
+
+    fn find_delegation(&self, name: &DomainName) -> Option<&[Answer]> {
+        // Walk down from just below the apex so the closest cut to the apex wins
+        name.zone_cut_candidates(&self.origin).find_map(|cut| {
+            let records = self.records.get(&cut)?;
+
+            records
+                .iter()
+                .any(|record| record.resource_type == ResourceType::NS)
+                .then_some(records.as_slice())
+        })
+    }
+
+    fn referral(&self, answers: Vec<Answer>, referral: &[Answer]) -> ZoneAnswer {
+        // Glue addresses are needed whenever the name servers live inside the delegated zone
+        let additionals = referral
+            .iter()
+            .filter_map(|record| match &record.data {
+                RData::NS(target) => self.records.get(target),
+                _ => None,
+            })
+            .flatten()
+            .filter(|record| matches!(record.resource_type, ResourceType::A | ResourceType::AAAA))
+            .cloned()
+            .collect();
+
+        ZoneAnswer {
+            authoritative: !answers.is_empty(),
+            response_code: ResponseCode::NoError,
+            answers,
+            authorities: referral
+                .iter()
+                .filter(|record| record.resource_type == ResourceType::NS)
+                .cloned()
+                .collect(),
+            additionals,
+        }
+    }
+
+    fn positive_answer(&self, answers: Vec<Answer>) -> ZoneAnswer {
+        ZoneAnswer {
+            authoritative: true,
+            response_code: ResponseCode::NoError,
+            answers,
+            authorities: vec![],
+            additionals: vec![],
+        }
+    }
+
+    fn negative_answer(&self, answers: Vec<Answer>, exists: bool) -> ZoneAnswer {
+        let mut soa = self.soa.clone();
+
+        // RFC 2308 §3: negative answers are cached for the lesser of the SOA TTL and MINIMUM
+        if let RData::SOA { minimum, .. } = soa.data {
+            soa.time_to_live = soa.time_to_live.min(minimum);
+        }
+
+        ZoneAnswer {
+            authoritative: true,
+            response_code: if exists {
+                ResponseCode::NoError
+            } else {
+                ResponseCode::NameError
+            },
+            answers,
+            authorities: vec![soa],
+            additionals: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ZoneParser {
+    parser: RecordParser,
+    records: Vec<Answer>,
+}
+
+impl ZoneParser {
+    fn parse_file(&mut self, path: &Path, depth: usize) -> Result<(), ZoneError> {
+        let contents = fs::read_to_string(path).map_err(|source| ZoneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let syntax_error = |line: usize, message: String| ZoneError::Syntax {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+
+        let entries = tokenize(&contents).map_err(|(line, message)| syntax_error(line, message))?;
+
+        for entry in entries {
+            let directive = if entry.owner_blank {
+                None
+            } else {
+                Some(entry.tokens[0].text.to_ascii_uppercase())
+            };
+
+            match directive.as_deref() {
+                Some("$ORIGIN") => {
+                    let [_, origin] = entry.tokens.as_slice() else {
+                        return Err(syntax_error(entry.line, "expected $ORIGIN <name>".into()));
+                    };
+
+                    self.parser.origin = Some(
+                        parse_name(&origin.text, self.parser.origin.as_ref())
+                            .map_err(|message| syntax_error(entry.line, message))?,
+                    );
+                }
+                Some("$TTL") => {
+                    let ttl = match entry.tokens.as_slice() {
+                        [_, ttl] => parse_ttl(&ttl.text),
+                        _ => None,
+                    };
+
+                    self.parser.default_ttl =
+                        Some(ttl.ok_or_else(|| {
+                            syntax_error(entry.line, "expected $TTL <ttl>".into())
+                        })?);
+                }
+                Some("$INCLUDE") => {
+                    let (file, origin) = match entry.tokens.as_slice() {
+                        [_, file] => (file, None),
+                        [_, file, origin] => (file, Some(origin)),
+                        _ => {
+                            return Err(syntax_error(
+                                entry.line,
+                                "expected $INCLUDE <file> [<origin>]".into(),
+                            ))
+                        }
+                    };
+
+                    if depth >= MAX_INCLUDE_DEPTH {
+                        return Err(syntax_error(
+                            entry.line,
+                            "$INCLUDE nested too deeply".into(),
+                        ));
+                    }
+
+                    let mut include = PathBuf::from(&file.text);
+                    if include.is_relative() {
+                        if let Some(parent) = path.parent() {
+                            include = parent.join(include);
+                        }
+                    }
+
+                    // The included file gets its own origin, which must not leak back out
+                    let outer_origin = self.parser.origin.clone();
+
+                    if let Some(origin) = origin {
+                        self.parser.origin = Some(
+                            parse_name(&origin.text, self.parser.origin.as_ref())
+                                .map_err(|message| syntax_error(entry.line, message))?,
+                        );
+                    }
+
+                    self.parse_file(&include, depth + 1)?;
+                    self.parser.origin = outer_origin;
+                }
+                Some(other) if other.starts_with('$') => {
+                    return Err(syntax_error(
+                        entry.line,
+                        format!("unknown directive {}", other),
+                    ));
+                }
+                _ => {
+                    let record = self
+                        .parser
+                        .parse_entry(&entry)
+                        .map_err(|message| syntax_error(entry.line, message))?;
+
+                    self.add_record(record)
+                        .map_err(|message| syntax_error(entry.line, message))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_record(&mut self, record: Answer) -> Result<(), String> {
+        match self.records.first() {
+            None if record.resource_type != ResourceType::SOA => {
+                Err("the first record in a zone must be its SOA".to_string())
+            }
+            Some(_) if record.resource_type == ResourceType::SOA => {
+                Err("a zone can only contain one SOA record".to_string())
+            }
+            Some(soa) if !record.name.is_subdomain_of(&soa.name) => {
+                Err("record is outside of the zone".to_string())
+            }
+            Some(soa) if record.class != soa.class => {
+                Err("record is not in the same class as the zone's SOA".to_string())
+            }
+            _ => {
+                self.records.push(record);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const SOA: &str = "$ORIGIN example.\n@ 3600 IN SOA ns admin 1 7200 3600 1209600 300\n";
+
+    // Writes the files into a directory of their own and loads the first one as the zone
+    fn load(test: &str, files: &[(&str, &str)]) -> Result<Zone, ZoneError> {
+        let dir = std::env::temp_dir().join(format!("zone-{}-{}", test, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+
+        let zone = Zone::load(dir.join(files[0].0));
+        fs::remove_dir_all(&dir).unwrap();
+
+        zone
+    }
+
+    fn lookup(zone: &Zone, name: &str, question_type: ResourceType) -> ZoneAnswer {
+        lookup_in(zone, name, question_type, ResourceClass::IN)
+    }
+
+    fn lookup_in(
+        zone: &Zone,
+        name: &str,
+        question_type: ResourceType,
+        question_class: ResourceClass,
+    ) -> ZoneAnswer {
+        zone.lookup(&Question {
+            name: name.parse().unwrap(),
+            question_type,
+            question_class,
+        })
+    }
+
+    fn names(records: &[Answer]) -> Vec<String> {
+        records
+            .iter()
+            .map(|record| format!("{} {}", record.name, record.resource_type))
+            .collect()
+    }
+
+    #[test]
+    fn negative_answers() {
+        let zone = load(
+            "negative",
+            &[("zone", &format!("{}a.b 60 IN A 192.0.2.1\n", SOA))],
+        )
+        .unwrap();
+
+        // b.example. only exists as the parent of a.b.example.
+        for (name, question_type, response_code) in [
+            ("b.example.", ResourceType::A, ResponseCode::NoError),
+            ("a.b.example.", ResourceType::MX, ResponseCode::NoError),
+            ("c.example.", ResourceType::A, ResponseCode::NameError),
+            ("c.a.b.example.", ResourceType::A, ResponseCode::NameError),
+        ] {
+            let answer = lookup(&zone, name, question_type);
+
+            assert_eq!(answer.response_code, response_code, "{}", name);
+            assert!(answer.authoritative);
+            assert!(answer.answers.is_empty());
+            assert_eq!(names(&answer.authorities), ["example. SOA"]);
+            // RFC 2308 §3: the lesser of the SOA TTL and MINIMUM
+            assert_eq!(answer.authorities[0].time_to_live, 300);
+        }
+    }
+
+    #[test]
+    fn referrals_include_glue() {
+        let zone = load(
+            "referral",
+            &[(
+                "zone",
+                &format!(
+                    "{}{}",
+                    SOA,
+                    "sub 60 IN NS ns.sub\n\
+                     sub 60 IN NS ns.elsewhere.net.\n\
+                     ns.sub 60 IN A 192.0.2.53\n\
+                     alias 60 IN CNAME www.sub\n"
+                ),
+            )],
+        )
+        .unwrap();
+
+        for name in ["sub.example.", "www.sub.example.", "ns.sub.example."] {
+            let answer = lookup(&zone, name, ResourceType::A);
+
+            assert_eq!(answer.response_code, ResponseCode::NoError);
+            assert!(!answer.authoritative, "{}", name);
+            assert!(answer.answers.is_empty());
+            assert_eq!(
+                names(&answer.authorities),
+                ["sub.example. NS", "sub.example. NS"]
+            );
+            assert_eq!(names(&answer.additionals), ["ns.sub.example. A"]);
+        }
+
+        // The alias itself is still answered authoritatively before the referral
+        let answer = lookup(&zone, "alias.example.", ResourceType::A);
+
+        assert!(answer.authoritative);
+        assert_eq!(names(&answer.answers), ["alias.example. CNAME"]);
+        assert_eq!(names(&answer.authorities).len(), 2);
+    }
+
+    #[test]
+    fn aliases_are_followed_inside_the_zone() {
+        let zone = load(
+            "cname",
+            &[(
+                "zone",
+                &format!(
+                    "{}{}",
+                    SOA,
+                    "www 60 IN CNAME web\n\
+                     web 60 IN CNAME host\n\
+                     host 60 IN A 192.0.2.1\n\
+                     out 60 IN CNAME www.elsewhere.net.\n\
+                     loop1 60 IN CNAME loop2\n\
+                     loop2 60 IN CNAME loop1\n"
+                ),
+            )],
+        )
+        .unwrap();
+
+        let answer = lookup(&zone, "www.example.", ResourceType::A);
+        assert_eq!(answer.response_code, ResponseCode::NoError);
+        assert_eq!(
+            names(&answer.answers),
+            [
+                "www.example. CNAME",
+                "web.example. CNAME",
+                "host.example. A"
+            ]
+        );
+        assert_eq!(
+            answer.answers[2].data,
+            RData::A(Ipv4Addr::new(192, 0, 2, 1))
+        );
+
+        // Asking for the alias itself doesn't follow it
+        let answer = lookup(&zone, "www.example.", ResourceType::CNAME);
+        assert_eq!(names(&answer.answers), ["www.example. CNAME"]);
+
+        // The target has no record of that type, so the chain ends in NODATA
+        let answer = lookup(&zone, "www.example.", ResourceType::MX);
+        assert_eq!(answer.response_code, ResponseCode::NoError);
+        assert_eq!(names(&answer.answers).len(), 2);
+        assert_eq!(names(&answer.authorities), ["example. SOA"]);
+
+        // Targets outside the zone are left for the resolver
+        let answer = lookup(&zone, "out.example.", ResourceType::A);
+        assert_eq!(names(&answer.answers), ["out.example. CNAME"]);
+        assert!(answer.authorities.is_empty());
+
+        let answer = lookup(&zone, "loop1.example.", ResourceType::A);
+        assert_eq!(answer.answers.len(), MAX_CNAME_CHAIN);
+    }
+
+    #[test]
+    fn included_files_keep_their_origin_to_themselves() {
+        let zone = load(
+            "include",
+            &[
+                (
+                    "zone",
+                    &format!(
+                        "{}$INCLUDE sub.zone sub.example.\nafter 60 IN A 192.0.2.2\n",
+                        SOA
+                    ),
+                ),
+                (
+                    "sub.zone",
+                    "inner 60 IN A 192.0.2.1\n$ORIGIN other.example.\nmoved 60 IN A 192.0.2.3\n",
+                ),
+            ],
+        )
+        .unwrap();
+
+        for name in [
+            "inner.sub.example.",
+            "moved.other.example.",
+            "after.example.",
+        ] {
+            let answer = lookup(&zone, name, ResourceType::A);
+
+            assert_eq!(names(&answer.answers), [format!("{} A", name)]);
+        }
+    }
+
+    #[test]
+    fn zones_have_one_soa_and_nothing_outside() {
+        let line = |result: Result<Zone, ZoneError>| match result {
+            Err(ZoneError::Syntax { line, .. }) => line,
+            other => panic!("expected a syntax error, got {:?}", other),
+        };
+
+        assert_eq!(
+            line(load(
+                "second-soa",
+                &[("zone", &format!("{}{}", SOA, SOA.lines().nth(1).unwrap()))]
+            )),
+            3
+        );
+        assert_eq!(
+            line(load(
+                "outside",
+                &[(
+                    "zone",
+                    &format!("{}www.example.net. 60 IN A 192.0.2.1\n", SOA)
+                )]
+            )),
+            3
+        );
+        assert_eq!(
+            line(load(
+                "no-soa-first",
+                &[("zone", "$ORIGIN example.\nwww 60 IN A 192.0.2.1\n")]
+            )),
+            2
+        );
+        assert_eq!(
+            line(load(
+                "other-class",
+                &[("zone", &format!("{}txt 60 CH TXT hello\n", SOA))]
+            )),
+            3
+        );
+        assert!(matches!(
+            load("empty", &[("zone", "; nothing here\n")]),
+            Err(ZoneError::MissingSoa { .. })
+        ));
+    }
+
+    #[test]
+    fn only_questions_in_the_zone_class_are_answered() {
+        let zone = load(
+            "class",
+            &[("zone", &format!("{}www 60 IN A 192.0.2.1\n", SOA))],
+        )
+        .unwrap();
+
+        let answer = lookup_in(&zone, "www.example.", ResourceType::A, ResourceClass::ANY);
+        assert_eq!(answer.response_code, ResponseCode::NoError);
+        assert_eq!(names(&answer.answers), ["www.example. A"]);
+
+        let answer = lookup_in(&zone, "www.example.", ResourceType::A, ResourceClass::CH);
+        assert_eq!(answer.response_code, ResponseCode::Refused);
+        assert!(!answer.authoritative);
+        assert!(answer.answers.is_empty());
+
+        let mut store = ZoneStore::new();
+        store.insert(zone);
+
+        let www = "www.example.".parse().unwrap();
+        assert!(store.find(&www, ResourceClass::IN).is_some());
+        assert!(store.find(&www, ResourceClass::ANY).is_some());
+        assert!(store.find(&www, ResourceClass::CH).is_none());
+    }
+}